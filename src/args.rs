@@ -1,94 +1,415 @@
-use crate::utils::str_from_file;
-use clap::Parser;
-use serde::Deserialize;
+use crate::{
+    daily, error::WordleError, game::Difficulty, pattern::Pattern, solver::StrategyKind, utils,
+    utils::str_from_file, words::Weighting,
+};
+use serde::{Deserialize, Deserializer};
 
-#[derive(Default, Parser, Debug, Deserialize)]
-#[clap(author, version, about, long_about = None)]
+/// Game settings, parsed from the command line (with feature `cli`)
+/// or from a json config file
+#[derive(Default, Debug, Clone, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::Parser))]
+#[cfg_attr(feature = "cli", clap(author, version, about, long_about = None))]
 pub struct Args {
     /// whether using tty
     /// should not be parsed by serde
-    #[clap(skip)]
+    #[cfg_attr(feature = "cli", clap(skip))]
     #[serde(default)]
     pub tty: bool,
 
     /// Specify the word to guess
-    #[clap(short, long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short, long, global = true, value_parser))]
     #[serde(default)]
     pub word: Option<String>,
 
     /// Enable random mode
-    #[clap(short, long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short, long, global = true, value_parser))]
     #[serde(default)]
     pub random: bool,
 
+    /// Enable adversarial mode: the answer is chosen as late as possible to dodge guesses
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub adversarial: bool,
+
     /// Enable hard mode
-    #[clap(short = 'D', long = "difficult", value_parser)]
+    #[cfg_attr(
+        feature = "cli",
+        clap(short = 'D', long = "difficult", global = true, value_parser)
+    )]
     #[serde(default)]
     pub difficult: bool,
 
+    /// Enable strict hard mode: grey letters, yellow letters at the same position
+    /// and letters used more than revealed are rejected too
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub strict: bool,
+
     /// Print statistic after every term
-    #[clap(short = 't', long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short = 't', long, global = true, value_parser))]
     #[serde(default)]
     pub stats: bool,
 
+    /// Play the puzzle of the day, the same for everyone sharing the word lists
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub daily: bool,
+
+    /// Play the daily puzzle of a past date, like 2022-01-31
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub date: Option<String>,
+
+    /// Print a spoiler-free summary with an emoji grid after each game
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub share: bool,
+
+    /// Use orange and blue instead of green and yellow in shared results
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub high_contrast: bool,
+
+    /// Also print the guess distribution and streaks with -t when not in a tty
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub histogram: bool,
+
+    /// Back up a corrupt state file and start fresh instead of aborting
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub recover: bool,
+
+    /// Skip random answers already played in the stats of --state
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub no_repeat: bool,
+
     /// Specify starting day
-    #[clap(short, long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short, long, global = true, value_parser))]
     #[serde(default)]
     pub day: Option<i32>,
 
+    /// Explain why a guess is INVALID when not using tty
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub verbose: bool,
+
+    /// Specify the length of words
+    #[cfg_attr(feature = "cli", clap(short, long, global = true, value_parser))]
+    #[serde(default)]
+    pub length: Option<usize>,
+
+    /// Specify the number of guesses in a game, 0 for unlimited
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub rounds: Option<usize>,
+
+    /// Specify the number of boards sharing guesses, e.g. 4 for Quordle
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
+    #[serde(default)]
+    pub boards: Option<usize>,
+
     /// Specify random seed
-    #[clap(short, long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short, long, global = true, value_parser))]
     #[serde(default)]
     pub seed: Option<u64>,
 
-    /// Specify final set
-    #[clap(short, long = "final-set", value_parser)]
+    /// Specify final set, repeat it to use the union of several lists
+    /// (`builtin` stands for the builtin one)
+    #[cfg_attr(
+        feature = "cli",
+        clap(short, long = "final-set", global = true, value_parser)
+    )]
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub final_set: Vec<String>,
+
+    /// Specify acceptable set, repeat it to use the union of several lists
+    /// (`builtin` stands for the builtin one)
+    #[cfg_attr(
+        feature = "cli",
+        clap(short, long = "acceptable-set", global = true, value_parser)
+    )]
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub acceptable_set: Vec<String>,
+
+    /// Leave the words of this list out of the final and acceptable sets, may be repeated
+    #[cfg_attr(
+        feature = "cli",
+        clap(long = "exclude-set", global = true, value_parser)
+    )]
+    #[serde(default, deserialize_with = "one_or_many")]
+    pub exclude_set: Vec<String>,
+
+    /// How random answers are drawn from the final set [default: uniform]
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_enum))]
+    #[serde(default)]
+    pub weighting: Option<Weighting>,
+
+    /// Only draw answers tagged <category> in the final set
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_parser))]
     #[serde(default)]
-    pub final_set: Option<String>,
+    pub category: Option<String>,
 
-    /// Specify acceptable set
-    #[clap(short, long = "acceptable-set", value_parser)]
+    /// Specify the strategy of the solver [default: entropy]
+    #[cfg_attr(feature = "cli", clap(long, global = true, value_enum))]
     #[serde(default)]
-    pub acceptable_set: Option<String>,
+    pub strategy: Option<StrategyKind>,
 
     /// store and load game state using file <state>
-    #[clap(short = 'S', long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short = 'S', long, global = true, value_parser))]
     #[serde(default)]
     pub state: Option<String>,
 
     /// Specify config file
-    #[clap(short, long, value_parser)]
+    #[cfg_attr(feature = "cli", clap(short, long, global = true, value_parser))]
     #[serde(default)]
     pub config: Option<String>,
+
+    /// Run a tool instead of playing
+    #[cfg_attr(feature = "cli", clap(subcommand))]
+    #[serde(skip)]
+    pub command: Option<Command>,
+}
+
+// word lists in a config file may be a single file or an array of them
+fn one_or_many<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<String>, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum OneOrMany {
+        One(String),
+        Many(Vec<String>),
+    }
+    Ok(match OneOrMany::deserialize(deserializer)? {
+        OneOrMany::One(file) => vec![file],
+        OneOrMany::Many(files) => files,
+    })
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(clap::Subcommand))]
+pub enum Command {
+    /// Rank the best guesses to start with, or solve the word given by --word
+    Solve {
+        /// Number of guesses to show
+        #[cfg_attr(
+            feature = "cli",
+            clap(short = 'n', long, default_value_t = 10, value_parser)
+        )]
+        top: usize,
+    },
+    /// Let each strategy (or the one given by --strategy) solve every word in the final set
+    Bench,
+    /// Help solving a puzzle whose answer is unknown: type each guess and
+    /// the colors it got (e.g. `CRANE RYRRG`), or just the colors if the
+    /// suggested guess was used
+    Assist,
+    /// List past daily puzzles (up to today, or --date) with how they went
+    /// according to --state, then play the one whose number is typed
+    Archive {
+        /// Number of puzzles to list
+        #[cfg_attr(
+            feature = "cli",
+            clap(short = 'n', long, default_value_t = 10, value_parser)
+        )]
+        count: usize,
+    },
+    /// Count results pasted as share texts (like `Wordle 123 4/6*` and its grid)
+    /// in the stats of --state, e.g. from a chat log
+    ImportShare {
+        /// File with the share texts, other lines are ignored
+        #[cfg_attr(feature = "cli", clap(value_parser))]
+        file: String,
+    },
+    /// Tools for the word lists of --final-set and --acceptable-set
+    Words {
+        #[cfg_attr(feature = "cli", clap(subcommand))]
+        command: WordsCommand,
+    },
+}
+
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "cli", derive(clap::Subcommand))]
+pub enum WordsCommand {
+    /// Report every problem of the word lists with its line, failing if one can't be played
+    Check {
+        /// Write the final set sorted, deduplicated and uppercased to this file
+        #[cfg_attr(feature = "cli", clap(long, value_parser))]
+        final_out: Option<String>,
+        /// Write the acceptable set sorted, deduplicated and uppercased to this file
+        #[cfg_attr(feature = "cli", clap(long, value_parser))]
+        acceptable_out: Option<String>,
+    },
 }
 
 impl Args {
-    pub fn refine(&mut self) {
+    pub fn difficulty(&self) -> Difficulty {
+        if self.strict {
+            Difficulty::Strict
+        } else if self.difficult {
+            Difficulty::Hard
+        } else {
+            Difficulty::Normal
+        }
+    }
+    // the flags giving <level>, e.g. to resume a game at the level it was started with
+    pub fn set_difficulty(&mut self, level: Difficulty) {
+        self.difficult = level >= Difficulty::Hard;
+        self.strict = level == Difficulty::Strict;
+    }
+    pub fn refine(&mut self) -> Result<(), WordleError> {
         // port config file into config
         if let Some(cfg) = self.config.as_ref() {
-            let alt_arg: Args = serde_json::from_str(&str_from_file(&cfg)).unwrap();
+            let err = |reason: String| WordleError::ConfigParse {
+                file: cfg.clone(),
+                reason,
+            };
+            let json = str_from_file(cfg).map_err(|e| err(e.to_string()))?;
+            let alt_arg: Args = serde_json::from_str(&json).map_err(|e| err(e.to_string()))?;
             self.word = self.word.take().or(alt_arg.word);
             self.random |= alt_arg.random;
+            self.daily |= alt_arg.daily;
+            self.adversarial |= alt_arg.adversarial;
             self.difficult |= alt_arg.difficult;
+            self.strict |= alt_arg.strict;
             self.stats |= alt_arg.stats;
+            self.verbose |= alt_arg.verbose;
+            self.share |= alt_arg.share;
+            self.histogram |= alt_arg.histogram;
+            self.recover |= alt_arg.recover;
+            self.no_repeat |= alt_arg.no_repeat;
+            self.high_contrast |= alt_arg.high_contrast;
             self.day = self.day.or(alt_arg.day);
+            self.date = self.date.take().or(alt_arg.date);
+            self.length = self.length.or(alt_arg.length);
+            self.rounds = self.rounds.or(alt_arg.rounds);
+            self.boards = self.boards.or(alt_arg.boards);
             self.seed = self.seed.or(alt_arg.seed);
-            self.final_set = self.final_set.take().or(alt_arg.final_set);
-            self.acceptable_set = self.acceptable_set.take().or(alt_arg.acceptable_set);
+            if self.final_set.is_empty() {
+                self.final_set = alt_arg.final_set;
+            }
+            if self.acceptable_set.is_empty() {
+                self.acceptable_set = alt_arg.acceptable_set;
+            }
+            if self.exclude_set.is_empty() {
+                self.exclude_set = alt_arg.exclude_set;
+            }
+            self.strategy = self.strategy.or(alt_arg.strategy);
+            self.weighting = self.weighting.or(alt_arg.weighting);
+            self.category = self.category.take().or(alt_arg.category);
             self.state = self.state.take().or(alt_arg.state);
             self.config = self.config.take().or(alt_arg.config);
         }
+        self.difficult |= self.strict;
         // random mode check
         if let Some(w) = self.word.as_ref() {
             self.word = Some(w.to_ascii_uppercase());
         }
+        // daily mode: the day is counted from the date
+        self.daily |= self.date.is_some() || matches!(self.command, Some(Command::Archive { .. }));
+        if self.daily {
+            if self.day.is_some() || self.word.is_some() || self.adversarial {
+                return Err(WordleError::ConflictingArgs(
+                    "-d, -w and --adversarial cannot be used in daily mode".to_string(),
+                ));
+            }
+            // anything else picking answers would change the puzzle of the day
+            if self.seed.is_some()
+                || self.category.is_some()
+                || self.weighting.is_some()
+                || !self.exclude_set.is_empty()
+            {
+                return Err(WordleError::ConflictingArgs(
+                    "-s, --category, --weighting and --exclude-set cannot be used in daily mode"
+                        .to_string(),
+                ));
+            }
+            self.seed = Some(daily::SEED);
+            let date = match self.date.as_ref() {
+                Some(d) => daily::parse_date(d).map_err(WordleError::InvalidArg)?,
+                None => daily::today(),
+            };
+            // a later puzzle would give its answer away
+            if date > daily::today() {
+                return Err(WordleError::InvalidArg(format!(
+                    "the puzzle of {} isn't out yet",
+                    daily::format_date(date)
+                )));
+            }
+            let number = daily::puzzle_number(date).ok_or_else(|| {
+                WordleError::InvalidArg(format!(
+                    "there's no puzzle before {}",
+                    daily::format_date(daily::epoch())
+                ))
+            })?;
+            self.date = Some(daily::format_date(date));
+            self.day = Some(number as i32 + 1);
+        }
+        // the answer is chosen by the guesses, no list order is involved
+        if self.adversarial && (self.random || self.seed.is_some() || self.day.is_some()) {
+            return Err(WordleError::ConflictingArgs(
+                "-r, -s and -d cannot be used in adversarial mode".to_string(),
+            ));
+        }
         if self.seed.is_some() || self.day.is_some() {
             self.random = true;
         }
+        // answers played before are only known from the stats of --state
+        if self.no_repeat && (!self.random || self.daily || self.state.is_none()) {
+            return Err(WordleError::ConflictingArgs(
+                "--no-repeat can only be used in random mode with --state".to_string(),
+            ));
+        }
+        if self.weighting.is_some() && !self.random {
+            return Err(WordleError::ConflictingArgs(
+                "--weighting can only be used in random mode".to_string(),
+            ));
+        }
         self.day = self.day.or(Some(1));
+        if self.day.unwrap() < 1 {
+            return Err(WordleError::InvalidArg(
+                "-d should be at least 1".to_string(),
+            ));
+        }
         self.seed = self.seed.or(Some(0));
+        self.length = self.length.or(Some(utils::LEN));
+        self.boards = self.boards.or(Some(1));
+        if self.boards.unwrap() == 0 {
+            return Err(WordleError::InvalidArg(
+                "--boards should be at least 1".to_string(),
+            ));
+        }
+        // every extra board gives an extra guess
+        let rounds = (utils::ROUNDS - 1)
+            .checked_add(self.boards.unwrap())
+            .ok_or_else(|| WordleError::InvalidArg("--boards is too large".to_string()))?;
+        self.rounds = self.rounds.or(Some(rounds));
+        // patterns pack each position in 2 bits
+        if !(1..=Pattern::MAX_LEN).contains(&self.length.unwrap()) {
+            return Err(WordleError::InvalidArg(format!(
+                "--length should be between 1 and {}",
+                Pattern::MAX_LEN
+            )));
+        }
+        if let Some(w) = self.word.as_ref() {
+            if w.len() != self.length.unwrap() {
+                return Err(WordleError::InvalidArg(format!(
+                    "-w {} doesn't have {} characters",
+                    w,
+                    self.length.unwrap()
+                )));
+            }
+        }
+        let conflict = |msg: &str| Err(WordleError::ConflictingArgs(msg.to_string()));
         if self.random && self.word.is_some() {
-            panic!("-w cannot be used in random mode");
+            return conflict("-w cannot be used in random mode");
+        }
+        if self.adversarial && self.word.is_some() {
+            return conflict("-w cannot be used in adversarial mode");
+        }
+        if self.boards.unwrap() > 1 && (self.word.is_some() || self.adversarial) {
+            return conflict("-w and --adversarial cannot be used with several boards");
         }
+        Ok(())
     }
 }
@@ -0,0 +1,59 @@
+//! Daily puzzles: puzzle N is played on the N-th day after `epoch()`,
+//! so everyone sharing a word list gets the same answer on the same day.
+
+use time::{Date, Duration, Month, OffsetDateTime};
+
+/// Seed of the shuffled final list every daily puzzle is picked from
+pub const SEED: u64 = 0;
+
+/// The day of puzzle 0
+pub fn epoch() -> Date {
+    Date::from_calendar_date(2021, Month::June, 19).unwrap()
+}
+
+/// The local date, or the UTC date if the local offset is unknown
+pub fn today() -> Date {
+    OffsetDateTime::now_local()
+        .unwrap_or_else(|_| OffsetDateTime::now_utc())
+        .date()
+}
+
+/// Parse a date like `2022-01-31`
+pub fn parse_date(s: &str) -> Result<Date, String> {
+    let err = || format!("{} isn't a date like 2022-01-31", s);
+    let parts: Vec<&str> = s.trim().split('-').collect();
+    if parts.len() != 3 {
+        return Err(err());
+    }
+    let year: i32 = parts[0].parse().map_err(|_| err())?;
+    let month: u8 = parts[1].parse().map_err(|_| err())?;
+    let day: u8 = parts[2].parse().map_err(|_| err())?;
+    let month = Month::try_from(month).map_err(|_| err())?;
+    Date::from_calendar_date(year, month, day).map_err(|_| err())
+}
+
+/// Format <date> the way `parse_date` reads it
+pub fn format_date(date: Date) -> String {
+    format!(
+        "{:04}-{:02}-{:02}",
+        date.year(),
+        date.month() as u8,
+        date.day()
+    )
+}
+
+/// The puzzle played on <date>, if <date> isn't before `epoch()`
+pub fn puzzle_number(date: Date) -> Option<usize> {
+    let days = (date - epoch()).whole_days();
+    if days < 0 {
+        None
+    } else {
+        Some(days as usize)
+    }
+}
+
+/// The day puzzle <number> is played, None if that's past the last representable date
+pub fn date_of(number: usize) -> Option<Date> {
+    let seconds = i64::try_from(number).ok()?.checked_mul(86_400)?;
+    epoch().checked_add(Duration::seconds(seconds))
+}
@@ -0,0 +1,151 @@
+//! Errors that stop the game before or while it's played.
+//!
+//! Each kind of failure exits with its own code, so that scripts can tell them apart:
+//!
+//! | code | error |
+//! |------|-------|
+//! | 1    | anything else, e.g. stdin can't be read |
+//! | 2    | invalid command line, reported by clap |
+//! | 3    | `ConflictingArgs` |
+//! | 4    | `InvalidArg` |
+//! | 5    | `ConfigParse` |
+//! | 6    | `WordListIo` |
+//! | 7    | `WordNotAcceptable` |
+//! | 8    | `EmptyWordList` |
+//! | 9    | `StateParse` |
+//! | 10   | `StateIo` |
+//! | 11   | `FileIo` |
+//! | 12   | `InvalidWord` |
+//! | 13   | `MalformedList` |
+//! | 14   | `StateVersion` |
+//! | 15   | `UnexpectedAnswer` |
+
+use std::fmt;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WordleError {
+    /// options that can't be used together
+    ConflictingArgs(String),
+    /// an option with a value that can't be played, like a date before the first puzzle
+    InvalidArg(String),
+    /// the --config file can't be read or isn't a valid config
+    ConfigParse { file: String, reason: String },
+    /// a word list can't be read
+    WordListIo { file: String, reason: String },
+    /// a word of the final list isn't acceptable, <line> is 1-based,
+    /// None for the builtin list
+    WordNotAcceptable { word: String, line: Option<usize> },
+    /// the final list has no word of the length played
+    EmptyWordList { len: usize },
+    /// the state file isn't valid
+    StateParse { file: String, reason: String },
+    /// the state file can't be read or written
+    StateIo { file: String, reason: String },
+    /// any other file can't be read or written
+    FileIo { file: String, reason: String },
+    /// a word list has a word that can't be played, like `it's`
+    InvalidWord {
+        file: String,
+        word: String,
+        line: Option<usize>,
+    },
+    /// a weighted line of a word list isn't `WORD WEIGHT [TAGS]`
+    MalformedList { file: String, line: usize },
+    /// the state file was written by a later version, it's left untouched
+    StateVersion { file: String, version: u32 },
+    /// the input isn't Y, N or empty when asked whether to <prompt>
+    UnexpectedAnswer { prompt: String, answer: String },
+}
+
+impl WordleError {
+    /// Process exit code, see the table in the module documentation
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            WordleError::ConflictingArgs(_) => 3,
+            WordleError::InvalidArg(_) => 4,
+            WordleError::ConfigParse { .. } => 5,
+            WordleError::WordListIo { .. } => 6,
+            WordleError::WordNotAcceptable { .. } => 7,
+            WordleError::EmptyWordList { .. } => 8,
+            WordleError::StateParse { .. } => 9,
+            WordleError::StateIo { .. } => 10,
+            WordleError::FileIo { .. } => 11,
+            WordleError::InvalidWord { .. } => 12,
+            WordleError::MalformedList { .. } => 13,
+            WordleError::StateVersion { .. } => 14,
+            WordleError::UnexpectedAnswer { .. } => 15,
+        }
+    }
+}
+
+impl fmt::Display for WordleError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WordleError::ConflictingArgs(msg) | WordleError::InvalidArg(msg) => {
+                write!(f, "{}", msg)
+            }
+            WordleError::ConfigParse { file, reason } => {
+                write!(f, "config file {} is invalid: {}", file, reason)
+            }
+            WordleError::WordListIo { file, reason } => {
+                write!(f, "cannot read word list {}: {}", file, reason)
+            }
+            WordleError::WordNotAcceptable {
+                word,
+                line: Some(line),
+            } => write!(
+                f,
+                "{} on line {} of the final set isn't in the acceptable set",
+                word, line
+            ),
+            WordleError::WordNotAcceptable { word, line: None } => {
+                write!(f, "{} of the final set isn't in the acceptable set", word)
+            }
+            WordleError::EmptyWordList { len } => {
+                write!(f, "final set contains no word with {} characters", len)
+            }
+            WordleError::StateParse { file, reason } => write!(
+                f,
+                "state file {} is corrupt ({}), use --recover to back it up and start fresh",
+                file, reason
+            ),
+            WordleError::StateIo { file, reason } => {
+                write!(f, "cannot access state file {}: {}", file, reason)
+            }
+            WordleError::FileIo { file, reason } => {
+                write!(f, "cannot access {}: {}", file, reason)
+            }
+            WordleError::InvalidWord {
+                file,
+                word,
+                line: Some(line),
+            } => write!(
+                f,
+                "{} on line {} of {} isn't made of letters only",
+                word, line, file
+            ),
+            WordleError::InvalidWord {
+                file,
+                word,
+                line: None,
+            } => write!(f, "{} of {} isn't made of letters only", word, file),
+            WordleError::MalformedList { file, line } => write!(
+                f,
+                "line {} of {} isn't like `WORD WEIGHT [TAG,TAG...]`",
+                line, file
+            ),
+            WordleError::StateVersion { file, version } => write!(
+                f,
+                "state file {} has version {}, newer than the supported {}, please upgrade",
+                file,
+                version,
+                crate::game::VERSION
+            ),
+            WordleError::UnexpectedAnswer { prompt, answer } => {
+                write!(f, "expected Y, N or nothing to {}, got {}", prompt, answer)
+            }
+        }
+    }
+}
+
+impl std::error::Error for WordleError {}
@@ -1,20 +1,163 @@
 use serde::{Deserialize, Serialize};
-use std::{cmp, collections::HashMap, fmt, iter::zip};
+use std::{
+    cmp,
+    collections::{HashMap, HashSet},
+    fmt,
+    iter::zip,
+};
 
-use crate::{utils, utils::apmax};
+use crate::{
+    daily,
+    error::WordleError,
+    pattern::{self, Pattern},
+    share::Shared,
+    utils,
+    utils::apmax,
+    words::Words,
+};
 
-#[derive(Debug, Default, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone)]
 struct State {
     answer: String,
     guesses: Vec<String>,
+    // guess budget of the game, 0 for unlimited,
+    // files from before version 3 omit it when it is the default one
+    #[serde(default = "State::default_rounds")]
+    rounds: usize,
+    // answers of every board in a multi-board game, where <answer> is left empty
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    answers: Vec<String>,
+    // date of the daily puzzle played, like 2022-01-31
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    date: Option<String>,
+    // number of the daily puzzle played, see `daily::puzzle_number`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    puzzle: Option<usize>,
+    // colors of each guess like GYRRG, only for results imported from share texts,
+    // where the guesses aren't known
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    patterns: Vec<String>,
+    // hard mode of a result imported from a share text, which has no <meta>,
+    // games played here keep it in <meta>
+    #[serde(default)]
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    hard: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    meta: Option<Meta>,
+}
+
+/// How the answer of a game was chosen
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Mode {
+    /// given by -w or typed
+    Fixed,
+    /// from the shuffled final list
+    Random,
+    /// by the date, see `daily`
+    Daily,
+    /// dodging guesses
+    Adversarial,
+}
+
+/// When and how a game was played, missing for games from version 1 state files
+/// and for imported results
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Meta {
+    /// unix timestamps in seconds, <end> is set once the game ends
+    pub start: i64,
+    #[serde(default)]
+    pub end: Option<i64>,
+    /// seconds from start to end
+    #[serde(default)]
+    pub duration: Option<i64>,
+    pub mode: Mode,
+    /// how hard the game was played
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    // the hard mode of version 3 files, see `Stats::load`
+    #[serde(default)]
+    #[serde(skip_serializing)]
+    hard: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<u64>,
+    /// index in the final list
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub day: Option<usize>,
+    /// `Words::fingerprint` of the word lists
+    pub words: String,
 }
 
-#[derive(Debug, Default, Serialize, Deserialize)]
+impl Meta {
+    // a game starting now
+    pub fn new(mode: Mode, seed: Option<u64>, day: Option<usize>, words: &Words) -> Meta {
+        Meta {
+            start: Self::now(),
+            end: None,
+            duration: None,
+            mode,
+            difficulty: Difficulty::Normal,
+            hard: false,
+            seed,
+            day,
+            words: words.fingerprint(),
+        }
+    }
+    fn now() -> i64 {
+        time::OffsetDateTime::now_utc().unix_timestamp()
+    }
+    fn finish(&mut self) {
+        let end = Self::now();
+        self.end = Some(end);
+        self.duration = Some(end - self.start);
+    }
+}
+
+/// Version of the state file format written, see `Stats::load` for older ones
+pub const VERSION: u32 = 4;
+
+/// Why `Stats::load` can't read a state file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadError {
+    /// not a state file at all
+    Invalid(String),
+    /// a state file of a format newer than `VERSION`, written by a later build
+    Newer(u32),
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LoadError::Invalid(reason) => write!(f, "{}", reason),
+            LoadError::Newer(version) => write!(
+                f,
+                "state file version {} is newer than the supported {}",
+                version, VERSION
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
 pub struct Stats {
+    // files without it are from before versioning
+    #[serde(default = "Stats::first_version")]
+    version: u32,
     #[serde(default)]
     total_rounds: i32,
     #[serde(default)]
     games: Vec<State>,
+    // the game being played when the state was saved, see `Stats::unfinished`
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    unfinished: Option<State>,
 }
 
 #[derive(Clone)]
@@ -23,19 +166,182 @@ pub struct Game {
     // 3: G, 2: Y, 1: R, 0: X
     // len26, stores each alpha's color
     col_alpha: Vec<i8>,
-    // len5, stores each position's color of latest guess
+    // len of word, stores each position's color of latest guess
     col_pos: Vec<i8>,
     // len26, stores how much times an alpha should be used at least
     lim_alpha: Vec<i8>,
+    // len26, stores how much times an alpha can be used at most
+    max_alpha: Vec<i8>,
+    // len of word, stores a bitmask of alphas that may still be at each position
+    allow_pos: Vec<u32>,
+    // colors of each guess in state.guesses
+    patterns: Vec<Pattern>,
+    // adversarial mode: answers still possible while the answer isn't chosen
+    pool: Option<Vec<String>>,
+}
+
+/// Why a guess is rejected in hard modes, positions are 0-based
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Violation {
+    /// <alpha> was revealed green at <pos>
+    Green { pos: usize, alpha: char },
+    /// <alpha> was revealed <count> times
+    AtLeast { alpha: char, count: i8 },
+    /// <alpha> was revealed yellow or grey at <pos>
+    NotAt { pos: usize, alpha: char },
+    /// <alpha> was revealed exactly <count> times
+    AtMost { alpha: char, count: i8 },
+    /// <alpha> is grey
+    Absent { alpha: char },
+}
+
+impl fmt::Display for Violation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Violation::Green { pos, alpha } => write!(f, "position {} must be {}", pos + 1, alpha),
+            Violation::AtLeast { alpha, count } => {
+                write!(f, "guess must contain at least {} {}", count, alpha)
+            }
+            Violation::NotAt { pos, alpha } => write!(f, "position {} can't be {}", pos + 1, alpha),
+            Violation::AtMost { alpha, count } => {
+                write!(f, "guess must contain at most {} {}", count, alpha)
+            }
+            Violation::Absent { alpha } => write!(f, "guess must not contain {}", alpha),
+        }
+    }
+}
+
+/// Guess distribution and streaks of the games counted by `Stats::feed_stats`
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Summary {
+    /// `distribution[i]`: games won with i+1 guesses
+    pub distribution: Vec<usize>,
+    pub failed: usize,
+    /// games won in a row up to the latest one
+    pub current_streak: usize,
+    pub max_streak: usize,
+}
+
+impl Summary {
+    pub fn played(&self) -> usize {
+        self.distribution.iter().sum::<usize>() + self.failed
+    }
+    /// percentage of games won
+    pub fn win_rate(&self) -> f64 {
+        match self.played() {
+            0 => 0f64,
+            played => (played - self.failed) as f64 * 100f64 / played as f64,
+        }
+    }
+}
+
+/// A game saved before it ended, see `Stats::unfinished`
+pub enum Unfinished {
+    Game(Box<Game>),
+    Boards(Boards),
+}
+
+impl Unfinished {
+    pub fn word_len(&self) -> usize {
+        match self {
+            Unfinished::Game(game) => game.word_len(),
+            Unfinished::Boards(boards) => boards.word_len(),
+        }
+    }
+    pub fn rounds(&self) -> usize {
+        match self {
+            Unfinished::Game(game) => game.rounds(),
+            Unfinished::Boards(boards) => boards.rounds(),
+        }
+    }
+    /// The game, or the first board, which tells the mode and puzzle of all
+    pub fn first(&self) -> &Game {
+        match self {
+            Unfinished::Game(game) => game,
+            Unfinished::Boards(boards) => &boards.boards()[0],
+        }
+    }
+    pub fn boards(&self) -> usize {
+        match self {
+            Unfinished::Game(_) => 1,
+            Unfinished::Boards(boards) => boards.boards().len(),
+        }
+    }
+}
+
+/// Result of a daily puzzle recorded in `Stats`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PuzzleStatus {
+    NotPlayed,
+    /// won with the number of guesses
+    Won(usize),
+    Lost,
+}
+
+/// How much of the revealed information a guess must use
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum Difficulty {
+    #[default]
+    Normal,
+    /// greens of the latest guess stay, yellows are used
+    Hard,
+    /// additionally no grey alpha, no yellow alpha at the same position,
+    /// and no alpha used more than revealed
+    Strict,
 }
 
 impl State {
-    pub fn new() -> State {
+    pub fn new(rounds: usize) -> State {
         State {
             answer: String::new(),
             guesses: Vec::<String>::new(),
+            rounds,
+            answers: vec![],
+            date: None,
+            puzzle: None,
+            patterns: vec![],
+            hard: false,
+            meta: None,
         }
     }
+    fn boards(&self) -> usize {
+        cmp::max(1, self.answers.len())
+    }
+    // number of guesses made
+    fn tries(&self) -> usize {
+        cmp::max(self.guesses.len(), self.patterns.len())
+    }
+    fn won(&self) -> bool {
+        if self.guesses.is_empty() {
+            return matches!(self.patterns.last(), Some(p) if p.chars().all(|c| c == 'G'));
+        }
+        match self.answers.is_empty() {
+            true => Some(&self.answer) == self.guesses.last(),
+            false => self.answers.iter().all(|a| self.guesses.contains(a)),
+        }
+    }
+    fn default_rounds() -> usize {
+        utils::ROUNDS
+    }
+    // unix timestamp of when the game was played, imported results count from the
+    // start of their puzzle's day; None for games from before meta was recorded
+    fn played_at(&self) -> Option<i64> {
+        match (&self.meta, self.puzzle) {
+            (Some(meta), _) => Some(meta.start),
+            (None, Some(puzzle)) => {
+                let date = daily::date_of(puzzle)?;
+                Some(date.midnight().assume_utc().unix_timestamp())
+            }
+            (None, None) => None,
+        }
+    }
+}
+
+impl Default for Stats {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Stats {
@@ -50,23 +356,142 @@ impl Stats {
 
     pub fn new() -> Stats {
         Stats {
+            version: VERSION,
             total_rounds: 0,
             games: vec![],
+            unfinished: None,
+        }
+    }
+    fn first_version() -> u32 {
+        1
+    }
+    /// Read a state file of this or an older version, migrated to `VERSION`
+    pub fn load(json: &str) -> Result<Stats, LoadError> {
+        let mut stats: Stats =
+            serde_json::from_str(json).map_err(|e| LoadError::Invalid(e.to_string()))?;
+        if stats.version > VERSION {
+            return Err(LoadError::Newer(stats.version));
+        }
+        // version 2 only adds optional fields to games,
+        // version 3 moves the hard mode of played games into their meta,
+        // version 4 records the level instead, strict games were only hard before
+        for state in stats.games.iter_mut().chain(stats.unfinished.as_mut()) {
+            if let Some(meta) = state.meta.as_mut() {
+                if std::mem::take(&mut state.hard) | std::mem::take(&mut meta.hard) {
+                    meta.difficulty = meta.difficulty.max(Difficulty::Hard);
+                }
+            }
         }
+        stats.version = VERSION;
+        Ok(stats)
     }
+    /// Answers of every game recorded, of each board in multi-board games.
+    /// Imported results have no known answer
+    pub fn answers(&self) -> HashSet<String> {
+        self.games
+            .iter()
+            .flat_map(|x| x.answers.iter().chain([&x.answer]))
+            .filter(|a| !a.is_empty())
+            .cloned()
+            .collect()
+    }
+    /// Whether the daily puzzle of <date> on <boards> boards is already counted
+    pub fn played(&self, date: &str, boards: usize) -> bool {
+        self.games
+            .iter()
+            .any(|x| x.date.as_deref() == Some(date) && x.boards() == boards)
+    }
+    /// How daily puzzle <puzzle> on <boards> boards went, by the first time it was played
+    pub fn puzzle_status(&self, puzzle: usize, boards: usize) -> PuzzleStatus {
+        match self
+            .games
+            .iter()
+            .find(|x| x.puzzle == Some(puzzle) && x.boards() == boards)
+        {
+            None => PuzzleStatus::NotPlayed,
+            Some(x) if x.won() => PuzzleStatus::Won(x.tries()),
+            Some(_) => PuzzleStatus::Lost,
+        }
+    }
+    /// Write the state file <file> atomically
+    pub fn save(&self, file: &str) -> Result<(), WordleError> {
+        let err = |reason: String| WordleError::StateIo {
+            file: file.to_string(),
+            reason,
+        };
+        let json = serde_json::to_string_pretty(self).map_err(|e| err(e.to_string()))?;
+        utils::str_to_file_atomic(&json, file).map_err(|e| err(e.to_string()))
+    }
+    // a daily puzzle played again isn't stored
     pub fn store_game(&mut self, game: Game) {
+        if let Some(date) = game.state.date.as_ref() {
+            if self.played(date, 1) {
+                return;
+            }
+        }
         self.total_rounds += 1;
         self.games.push(game.state);
     }
-    // return win_rounds, lose_rounds, avg_guesses
-    pub fn feed_stats(&self) -> (i32, i32, f64) {
-        let (win_rounds, win_guesses) = self
+    pub fn store_boards(&mut self, boards: Boards) {
+        if let Some(date) = boards.games[0].state.date.as_ref() {
+            if self.played(date, boards.games.len()) {
+                return;
+            }
+        }
+        self.total_rounds += 1;
+        self.games.push(boards.to_state());
+    }
+    /// Keep <game> in the state file until it ends.
+    /// Adversarial games can't be resumed, since the answers left aren't saved
+    pub fn set_unfinished(&mut self, game: &Game) {
+        if game.pool.is_none() {
+            self.unfinished = Some(game.state.clone());
+        }
+    }
+    pub fn set_unfinished_boards(&mut self, boards: &Boards) {
+        self.unfinished = Some(boards.to_state());
+    }
+    pub fn clear_unfinished(&mut self) {
+        self.unfinished = None;
+    }
+    /// The game left unfinished, rebuilt by replaying its guesses
+    pub fn unfinished(&self) -> Option<Unfinished> {
+        let state = self.unfinished.clone()?;
+        Some(match state.answers.is_empty() {
+            true => Unfinished::Game(Box::new(Game::from_state(state))),
+            false => Unfinished::Boards(Boards::from_state(state)),
+        })
+    }
+    /// Store a result imported from a share text, unless its puzzle is already counted.
+    /// `share::parse` only gives puzzles that have a date
+    pub fn store_shared(&mut self, shared: &Shared) -> bool {
+        let date = daily::date_of(shared.puzzle).expect("puzzle without a date");
+        let date = daily::format_date(date);
+        if self.played(&date, 1) {
+            return false;
+        }
+        let mut state = State::new(shared.rounds);
+        state.puzzle = Some(shared.puzzle);
+        state.date = Some(date);
+        state.hard = shared.hard;
+        state.patterns = shared.patterns.iter().map(|p| p.to_string()).collect();
+        self.total_rounds += 1;
+        self.games.push(state);
+        true
+    }
+    // return win_rounds, lose_rounds, avg_guesses of games
+    // with guess budget <rounds> played on <boards> boards
+    pub fn feed_stats(&self, rounds: usize, boards: usize) -> (i32, i32, f64) {
+        let games = self
             .games
             .iter()
-            .filter(|x| Some(&x.answer) == x.guesses.last())
-            .map(|x| (1, x.guesses.len()))
+            .filter(|x| x.rounds == rounds && x.boards() == boards);
+        let (win_rounds, win_guesses) = games
+            .clone()
+            .filter(|x| x.won())
+            .map(|x| (1, x.tries()))
             .fold((0, 0), |acc, x| (acc.0 + x.0, acc.1 + x.1));
-        let lose_rounds = self.total_rounds - win_rounds;
+        let lose_rounds = games.count() as i32 - win_rounds;
         let avg_guesses = if win_rounds == 0 {
             0f64
         } else {
@@ -74,6 +499,35 @@ impl Stats {
         };
         (win_rounds, lose_rounds, avg_guesses)
     }
+    // guess distribution and streaks of games with guess budget <rounds>
+    // played on <boards> boards, the distribution covers every budgeted guess
+    pub fn feed_summary(&self, rounds: usize, boards: usize) -> Summary {
+        let mut summary = Summary {
+            distribution: vec![0; rounds],
+            ..Default::default()
+        };
+        let mut games: Vec<&State> = self
+            .games
+            .iter()
+            .filter(|x| x.rounds == rounds && x.boards() == boards)
+            .collect();
+        // imported results are stored last, whenever their puzzle was played
+        games.sort_by_key(|x| x.played_at());
+        for game in games {
+            if game.won() {
+                if summary.distribution.len() < game.tries() {
+                    summary.distribution.resize(game.tries(), 0);
+                }
+                summary.distribution[game.tries() - 1] += 1;
+                summary.current_streak += 1;
+                apmax(&mut summary.max_streak, summary.current_streak);
+            } else {
+                summary.failed += 1;
+                summary.current_streak = 0;
+            }
+        }
+        summary
+    }
     pub fn feed_words(&self) -> Vec<(&str, i32)> {
         let mut map = HashMap::<&str, i32>::new();
         // load stats into helper vaiables
@@ -91,8 +545,12 @@ impl Stats {
         w_list.truncate(5);
         w_list
     }
-    pub fn print_stats(&self, is_tty: bool) {
-        let (win_rounds, lose_rounds, avg_guesses) = self.feed_stats();
+    #[cfg(feature = "cli")]
+    // <histogram>: also print the summary in non-tty mode,
+    // as `1:n1 2:n2 ... X:failed current_streak max_streak win_rate`
+    pub fn print_stats(&self, is_tty: bool, rounds: usize, boards: usize, histogram: bool) {
+        let (win_rounds, lose_rounds, avg_guesses) = self.feed_stats(rounds, boards);
+        let summary = self.feed_summary(rounds, boards);
         let w_list = self.feed_words();
         if is_tty {
             let win_colored = console::style(format!("Win: {}", win_rounds)).green();
@@ -101,6 +559,13 @@ impl Stats {
                 "{}, {}, Avg guesses: {:.2}",
                 win_colored, lose_colored, avg_guesses
             );
+            println!(
+                "Win %: {:.0}, Current streak: {}, Max streak: {}",
+                summary.win_rate(),
+                summary.current_streak,
+                summary.max_streak
+            );
+            Self::print_histogram(&summary);
             println!("Used most:");
             for (i, w) in w_list.iter().enumerate() {
                 if i != 0 {
@@ -117,8 +582,49 @@ impl Stats {
                 }
                 print!("{} {}", w.0, w.1);
             }
+            if histogram {
+                println!();
+                let distribution: Vec<String> = summary
+                    .distribution
+                    .iter()
+                    .enumerate()
+                    .map(|(i, n)| format!("{}:{}", i + 1, n))
+                    .collect();
+                print!(
+                    "{} X:{} {} {} {:.2}",
+                    distribution.join(" "),
+                    summary.failed,
+                    summary.current_streak,
+                    summary.max_streak,
+                    summary.win_rate()
+                );
+            }
         }
-        println!("");
+        println!();
+    }
+    #[cfg(feature = "cli")]
+    fn print_histogram(summary: &Summary) {
+        const WIDTH: usize = 30;
+        let most = summary
+            .distribution
+            .iter()
+            .copied()
+            .chain([summary.failed])
+            .max()
+            .unwrap_or(0);
+        let bar = |n: usize| match most {
+            0 => String::new(),
+            // any game gets a visible bar
+            most => "\u{2588}".repeat(cmp::max(n * WIDTH / most, cmp::min(n, 1))),
+        };
+        for (i, n) in summary.distribution.iter().enumerate() {
+            println!("{:>2} {} {}", i + 1, console::style(bar(*n)).green(), n);
+        }
+        println!(
+            " X {} {}",
+            console::style(bar(summary.failed)).red(),
+            summary.failed
+        );
     }
 }
 
@@ -133,11 +639,17 @@ impl fmt::Display for Game {
     }
 }
 
+impl Default for Game {
+    fn default() -> Self {
+        Self::new(utils::LEN, utils::ROUNDS)
+    }
+}
+
 impl Game {
-    fn alpha2id(c: char) -> usize {
+    pub(crate) fn alpha2id(c: char) -> usize {
         c as usize - 'A' as usize
     }
-    fn color2id(c: char) -> i8 {
+    pub(crate) fn color2id(c: char) -> i8 {
         match c {
             'G' => 3,
             'Y' => 2,
@@ -146,7 +658,7 @@ impl Game {
             _ => panic!("Unknown color: {}", c),
         }
     }
-    fn id2color(id: i8) -> char {
+    pub(crate) fn id2color(id: i8) -> char {
         match id {
             3 => 'G',
             2 => 'Y',
@@ -156,34 +668,82 @@ impl Game {
         }
     }
 
-    pub fn new() -> Game {
+    // rounds: guess budget, 0 for unlimited
+    pub fn new(len: usize, rounds: usize) -> Game {
         Game {
-            state: State::new(),
+            state: State::new(rounds),
             col_alpha: vec![0i8; 26],
-            col_pos: vec![0i8; utils::LEN],
+            col_pos: vec![0i8; len],
             lim_alpha: vec![0i8; 26],
+            max_alpha: vec![i8::MAX; 26],
+            allow_pos: vec![(1 << 26) - 1; len],
+            patterns: vec![],
+            pool: None,
         }
     }
-    pub fn won(&self) -> bool {
-        match self.state.guesses.len() {
-            0 => false,
-            l => self.state.guesses[l - 1] == self.state.answer,
+    // replay the guesses of <state> to rebuild the colors and constraints
+    fn from_state(mut state: State) -> Game {
+        let guesses = std::mem::take(&mut state.guesses);
+        let mut game = Game::new(state.answer.len(), state.rounds);
+        game.state = state;
+        for guess in guesses {
+            game.guess(guess);
         }
+        game
+    }
+    pub fn won(&self) -> bool {
+        matches!(self.patterns.last(), Some(p) if p.solved())
     }
     pub fn ended(&self) -> bool {
         let stat = &self.state;
-        match stat.guesses.len() {
-            utils::ROUNDS => true,
-            _ => self.won(),
+        match stat.rounds {
+            0 => self.won(),
+            r => stat.guesses.len() >= r || self.won(),
         }
     }
+    pub fn word_len(&self) -> usize {
+        self.col_pos.len()
+    }
     pub fn rounds(&self) -> usize {
         self.state.guesses.len()
     }
     pub fn set_answer(&mut self, answer: String) {
+        assert!(answer.len() == self.word_len());
         self.state.answer = answer;
     }
-    pub fn vec2str(v: &Vec<i8>) -> String {
+    // record how the game is played, starting the clock
+    pub fn set_meta(&mut self, meta: Meta, difficulty: Difficulty) {
+        self.state.meta = Some(Meta { difficulty, ..meta });
+    }
+    pub fn meta(&self) -> Option<&Meta> {
+        self.state.meta.as_ref()
+    }
+    // how hard the game is played, imported results only tell whether it's hard
+    pub fn difficulty(&self) -> Difficulty {
+        match (self.state.meta.as_ref(), self.state.hard) {
+            (Some(meta), _) => meta.difficulty,
+            (None, true) => Difficulty::Hard,
+            (None, false) => Difficulty::Normal,
+        }
+    }
+    // mark the game as daily puzzle <puzzle>, recording its date
+    pub fn set_puzzle(&mut self, puzzle: usize) {
+        self.state.puzzle = Some(puzzle);
+        self.state.date = daily::date_of(puzzle).map(daily::format_date);
+    }
+    pub fn puzzle(&self) -> Option<usize> {
+        self.state.puzzle
+    }
+    pub fn date(&self) -> Option<&str> {
+        self.state.date.as_deref()
+    }
+    // choose the answer among <pool> as late as possible, dodging every guess
+    pub fn set_adversarial(&mut self, pool: Vec<String>) {
+        assert!(!pool.is_empty());
+        self.state.answer = String::new();
+        self.pool = Some(pool);
+    }
+    pub fn vec2str(v: &[i8]) -> String {
         v.iter().map(|x| Self::id2color(*x)).collect()
     }
     pub fn show_answer(&self) -> &str {
@@ -192,61 +752,357 @@ impl Game {
     pub fn show_col(&self) -> (&Vec<i8>, &Vec<i8>) {
         (&self.col_pos, &self.col_alpha)
     }
-    pub fn hard_check(&self, guess: &str) -> bool {
-        let mut cnt_alpha = vec![0i8; 26];
-        // ensure user uses all green state
-        for (i, (ca, cg)) in zip(self.state.answer.chars(), guess.chars()).enumerate() {
+    pub fn show_guesses(&self) -> &[String] {
+        &self.state.guesses
+    }
+    pub fn show_patterns(&self) -> &[Pattern] {
+        &self.patterns
+    }
+    /// Spoiler-free summary like `Wordle 123 4/6*`, then a row of squares per guess.
+    /// The puzzle number is only shown for daily puzzles, `*` marks hard modes,
+    /// the score is `X` for a lost game and `-` while it's still being played,
+    /// <high_contrast> uses orange and blue instead of green and yellow
+    pub fn share(&self, hard: bool, high_contrast: bool) -> String {
+        let mut text = share_header(
+            self.puzzle(),
+            self.won(),
+            self.ended(),
+            self.rounds(),
+            self.state.rounds,
+            hard,
+        );
+        for pattern in self.patterns.iter() {
+            text.push('\n');
+            text += &share_squares(Some(pattern), self.word_len(), high_contrast);
+        }
+        text
+    }
+    // words in <pool> that would have produced the same patterns for all guesses so far
+    pub fn candidates_in<'a, I>(&self, pool: I) -> Vec<&'a str>
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        pool.into_iter()
+            .map(|w| w.as_str())
+            .filter(|w| w.len() == self.word_len())
+            .filter(|w| {
+                // fast path: alphas known to be used must be there
+                let mut cnt_alpha = [0i8; 26];
+                w.chars().for_each(|c| cnt_alpha[Self::alpha2id(c)] += 1);
+                zip(self.lim_alpha.iter(), cnt_alpha.iter()).all(|(lim, cnt)| lim <= cnt)
+            })
+            .filter(|w| {
+                zip(self.state.guesses.iter(), self.patterns.iter())
+                    .all(|(guess, pattern)| pattern::score(w, guess) == *pattern)
+            })
+            .collect()
+    }
+    // possible answers in final list
+    pub fn candidates<'a>(&self, words: &'a Words) -> Vec<&'a str> {
+        self.candidates_in(&words.final_list)
+    }
+    fn id2alpha(id: usize) -> char {
+        (b'A' + id as u8) as char
+    }
+    pub fn hard_check(&self, guess: &str) -> Result<(), Violation> {
+        let mut cnt_alpha = [0i8; 26];
+        // ensure user uses all green state of the latest guess
+        let last = self.state.guesses.last().map_or("", |s| s.as_str());
+        for (i, (cl, cg)) in zip(last.chars(), guess.chars()).enumerate() {
             let col = Self::id2color(self.col_pos[i]);
-            if col == 'G' && ca != cg {
-                return false;
+            if col == 'G' && cl != cg {
+                return Err(Violation::Green { pos: i, alpha: cl });
             }
-            cnt_alpha[Self::alpha2id(cg)] += 1;
         }
+        guess
+            .chars()
+            .for_each(|cg| cnt_alpha[Self::alpha2id(cg)] += 1);
         // ensure user uses all yellow state
-        for i in 0..cnt_alpha.len() {
-            if self.lim_alpha[i] > cnt_alpha[i] {
-                return false;
+        for (a, (lim, cnt)) in zip(self.lim_alpha.iter(), cnt_alpha.iter()).enumerate() {
+            if lim > cnt {
+                let alpha = Self::id2alpha(a);
+                return Err(Violation::AtLeast { alpha, count: *lim });
             }
         }
-        true
+        Ok(())
+    }
+    pub fn strict_check(&self, guess: &str) -> Result<(), Violation> {
+        self.hard_check(guess)?;
+        // ensure user doesn't use an alpha more than revealed
+        let mut cnt_alpha = [0i8; 26];
+        guess
+            .chars()
+            .for_each(|cg| cnt_alpha[Self::alpha2id(cg)] += 1);
+        for (a, (max, cnt)) in zip(self.max_alpha.iter(), cnt_alpha.iter()).enumerate() {
+            if cnt > max {
+                let alpha = Self::id2alpha(a);
+                return Err(match max {
+                    0 => Violation::Absent { alpha },
+                    _ => Violation::AtMost { alpha, count: *max },
+                });
+            }
+        }
+        // ensure user doesn't put an alpha where it's known not to be
+        for (i, cg) in guess.chars().enumerate() {
+            let allow = self.allow_pos[i];
+            if allow >> Self::alpha2id(cg) & 1 == 0 {
+                return Err(match allow.count_ones() {
+                    1 => Violation::Green {
+                        pos: i,
+                        alpha: Self::id2alpha(allow.trailing_zeros() as usize),
+                    },
+                    _ => Violation::NotAt { pos: i, alpha: cg },
+                });
+            }
+        }
+        Ok(())
+    }
+    pub fn check(&self, guess: &str, level: Difficulty) -> Result<(), Violation> {
+        match level {
+            Difficulty::Normal => Ok(()),
+            Difficulty::Hard => self.hard_check(guess),
+            Difficulty::Strict => self.strict_check(guess),
+        }
     }
 
     pub fn guess(&mut self, guess: String) -> bool {
-        assert!(guess.len() == utils::LEN);
-        self.state.guesses.push(guess.clone());
-        let answer = self.state.answer.clone();
-        let mut cnt_alpha = vec![0i8; 26];
-        for ca in answer.chars() {
-            cnt_alpha[Self::alpha2id(ca)] += 1;
-        }
-        let req_alpha = cnt_alpha.clone();
-        // color good position to G
-        for (i, (ca, cg)) in zip(answer.chars(), guess.chars()).enumerate() {
-            if ca == cg {
-                let alpha_id = Self::alpha2id(ca);
-                let color_id = Self::color2id('G');
-                cnt_alpha[alpha_id] -= 1;
-                self.col_pos[i] = color_id;
-                self.col_alpha[alpha_id] = color_id;
-            }
-        }
-        // color other position
-        for (i, (ca, cg)) in zip(answer.chars(), guess.chars()).enumerate() {
+        let pattern = match self.pool.take() {
+            None => pattern::score(&self.state.answer, &guess),
+            Some(pool) => {
+                let (pattern, mut pool) = Self::dodge(pool, &guess);
+                if pool.len() == 1 {
+                    self.state.answer = pool.pop().unwrap();
+                } else {
+                    self.pool = Some(pool);
+                }
+                pattern
+            }
+        };
+        let won = self.record(guess, pattern);
+        // the game is over before the answer is chosen
+        if self.ended() && self.state.answer.is_empty() {
+            self.state.answer = self.pool.take().unwrap().swap_remove(0);
+        }
+        won
+    }
+
+    // keep the largest group of <pool> sharing the same pattern for <guess>,
+    // on ties the one revealing the least
+    fn dodge(pool: Vec<String>, guess: &str) -> (Pattern, Vec<String>) {
+        let mut buckets = HashMap::<Pattern, Vec<String>>::new();
+        for w in pool {
+            buckets
+                .entry(pattern::score(&w, guess))
+                .or_default()
+                .push(w);
+        }
+        buckets
+            .into_iter()
+            .max_by_key(|(p, ws)| {
+                let revealed: i32 = p.iter().map(|id| id as i32).sum();
+                (ws.len(), cmp::Reverse(revealed), cmp::Reverse(p.code()))
+            })
+            .unwrap()
+    }
+
+    // apply colors of <guess> observed elsewhere, the answer isn't needed
+    pub fn record(&mut self, guess: String, pattern: Pattern) -> bool {
+        assert!(guess.len() == self.word_len() && pattern.len() == self.word_len());
+        // how many times each alpha is colored G or Y in this guess
+        let mut cnt_alpha = [0i8; 26];
+        // whether each alpha is colored R in this guess
+        let mut red_alpha = [false; 26];
+        for (i, (cg, color_id)) in zip(guess.chars(), pattern.iter()).enumerate() {
             let alpha_id = Self::alpha2id(cg);
-            if ca != cg {
-                cnt_alpha[alpha_id] -= 1;
-                let color_id = Self::color2id(if cnt_alpha[alpha_id] >= 0 { 'Y' } else { 'R' });
-                self.col_pos[i] = color_id;
-                apmax(&mut self.col_alpha[alpha_id], color_id);
+            self.col_pos[i] = color_id;
+            apmax(&mut self.col_alpha[alpha_id], color_id);
+            if color_id == Self::color2id('G') {
+                self.allow_pos[i] = 1 << alpha_id;
+            } else {
+                self.allow_pos[i] &= !(1 << alpha_id);
+            }
+            if color_id == Self::color2id('R') {
+                red_alpha[alpha_id] = true;
+            } else {
+                cnt_alpha[alpha_id] += 1;
             }
         }
         // calc how many times should an alpha be used at least
-        for a in 0..self.lim_alpha.len() {
-            apmax(
-                &mut self.lim_alpha[a],
-                req_alpha[a] - cmp::max(0i8, cnt_alpha[a]),
-            );
+        for (lim, cnt) in zip(self.lim_alpha.iter_mut(), cnt_alpha.iter()) {
+            apmax(lim, *cnt);
+        }
+        // an R tile reveals the exact count
+        for (a, cnt) in cnt_alpha.iter().enumerate() {
+            if red_alpha[a] {
+                self.max_alpha[a] = cmp::min(self.max_alpha[a], *cnt);
+            }
+        }
+        self.state.guesses.push(guess);
+        self.patterns.push(pattern);
+        if self.ended() {
+            if let Some(meta) = self.state.meta.as_mut() {
+                meta.finish();
+            }
+        }
+        pattern.solved()
+    }
+}
+
+// first line of shared results, the score is X for lost games and - for unfinished ones
+fn share_header(
+    puzzle: Option<usize>,
+    won: bool,
+    ended: bool,
+    guesses: usize,
+    rounds: usize,
+    hard: bool,
+) -> String {
+    let mut text = String::from("Wordle");
+    if let Some(puzzle) = puzzle {
+        text += &format!(" {}", puzzle);
+    }
+    let score = match (won, ended) {
+        (true, _) => guesses.to_string(),
+        (false, true) => "X".to_string(),
+        (false, false) => "-".to_string(),
+    };
+    match rounds {
+        0 => text += &format!(" {}", score),
+        rounds => text += &format!(" {}/{}", score, rounds),
+    }
+    if hard {
+        text.push('*');
+    }
+    text
+}
+
+// a square per position, R and X are both grey; white squares if there's no pattern
+fn share_squares(pattern: Option<&Pattern>, len: usize, high_contrast: bool) -> String {
+    match pattern {
+        None => "\u{2B1C}".repeat(len),
+        Some(pattern) => pattern
+            .iter()
+            .map(|id| match (id, high_contrast) {
+                (3, false) => '\u{1F7E9}',
+                (3, true) => '\u{1F7E7}',
+                (2, false) => '\u{1F7E8}',
+                (2, true) => '\u{1F7E6}',
+                _ => '\u{2B1B}',
+            })
+            .collect(),
+    }
+}
+
+/// Several games sharing one guess stream (Dordle, Quordle...),
+/// a board stops taking guesses once it's solved
+pub struct Boards {
+    games: Vec<Game>,
+    guesses: Vec<String>,
+    rounds: usize,
+}
+
+impl Boards {
+    // rounds: guess budget, 0 for unlimited
+    pub fn new(len: usize, rounds: usize, answers: Vec<String>) -> Boards {
+        Boards {
+            games: answers
+                .into_iter()
+                .map(|a| {
+                    let mut game = Game::new(len, rounds);
+                    game.set_answer(a);
+                    game
+                })
+                .collect(),
+            guesses: vec![],
+            rounds,
         }
-        guess == self.state.answer
+    }
+    // replay the guesses of <state>, a multi-board game saved by `to_state`
+    fn from_state(state: State) -> Boards {
+        let mut boards = Boards::new(state.answers[0].len(), state.rounds, state.answers);
+        for game in boards.games.iter_mut() {
+            game.state.date = state.date.clone();
+            game.state.puzzle = state.puzzle;
+            game.state.meta = state.meta.clone();
+        }
+        for guess in state.guesses {
+            boards.guess(guess);
+        }
+        boards
+    }
+    fn to_state(&self) -> State {
+        let mut state = State::new(self.rounds);
+        state.date = self.games[0].state.date.clone();
+        state.puzzle = self.games[0].state.puzzle;
+        // boards end one by one, the game ends with the last one
+        state.meta = self
+            .games
+            .iter()
+            .filter_map(|g| g.state.meta.clone())
+            .max_by_key(|m| m.end);
+        state.guesses = self.guesses.clone();
+        state.answers = self.games.iter().map(|g| g.state.answer.clone()).collect();
+        state
+    }
+    pub fn set_puzzle(&mut self, puzzle: usize) {
+        self.games.iter_mut().for_each(|g| g.set_puzzle(puzzle));
+    }
+    pub fn set_meta(&mut self, meta: Meta, difficulty: Difficulty) {
+        self.games
+            .iter_mut()
+            .for_each(|g| g.set_meta(meta.clone(), difficulty));
+    }
+    pub fn boards(&self) -> &[Game] {
+        &self.games
+    }
+    pub fn word_len(&self) -> usize {
+        self.games[0].word_len()
+    }
+    pub fn rounds(&self) -> usize {
+        self.guesses.len()
+    }
+    pub fn won(&self) -> bool {
+        self.games.iter().all(|g| g.won())
+    }
+    pub fn ended(&self) -> bool {
+        self.won() || (self.rounds != 0 && self.guesses.len() >= self.rounds)
+    }
+    /// Like `Game::share`, with the rows of every board side by side,
+    /// left white once the board is solved
+    pub fn share(&self, hard: bool, high_contrast: bool) -> String {
+        let puzzle = self.games[0].puzzle();
+        let mut text = share_header(
+            puzzle,
+            self.won(),
+            self.ended(),
+            self.rounds(),
+            self.rounds,
+            hard,
+        );
+        for i in 0..self.rounds() {
+            text.push('\n');
+            let row: Vec<String> = self
+                .games
+                .iter()
+                .map(|g| share_squares(g.patterns.get(i), self.word_len(), high_contrast))
+                .collect();
+            text += &row.join(" ");
+        }
+        text
+    }
+    // the guess must fit every board still being played
+    pub fn check(&self, guess: &str, level: Difficulty) -> Result<(), Violation> {
+        self.games
+            .iter()
+            .filter(|g| !g.won())
+            .try_for_each(|g| g.check(guess, level))
+    }
+    // return whether every board is solved
+    pub fn guess(&mut self, guess: String) -> bool {
+        self.games.iter_mut().filter(|g| !g.won()).for_each(|g| {
+            g.guess(guess.clone());
+        });
+        self.guesses.push(guess);
+        self.won()
     }
 }
@@ -0,0 +1,13 @@
+//! Wordle game engine shared by the terminal (`wordle`) and browser
+//! (`wordle-web`) frontends.
+
+pub mod args;
+pub mod builtin_words;
+pub mod daily;
+pub mod error;
+pub mod game;
+pub mod pattern;
+pub mod share;
+pub mod solver;
+pub mod utils;
+pub mod words;
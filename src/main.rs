@@ -1,127 +1,688 @@
-use console;
-use rand::{SeedableRng, prelude::SliceRandom};
+use clap::Parser;
 use std::{
+    collections::HashSet,
+    fs,
     io::{self, Write},
-    collections::HashSet
+};
+use wordle::{
+    args::{Args, Command, WordsCommand},
+    daily,
+    error::WordleError,
+    game::{Boards, Difficulty, Game, LoadError, Meta, Mode, PuzzleStatus, Stats, Unfinished, Violation},
+    pattern::Pattern,
+    share,
+    solver::{self, StrategyKind},
+    utils, words,
 };
 
-mod args;
-use args::Args;
-use clap::Parser;
-
-mod utils;
+// problems of the word lists shown at startup
+const MAX_WARNINGS: usize = 5;
 
-mod game;
-use game::{Game, Stats};
+// what reading a guess needs to know, for one board or several
+trait Board {
+    fn word_len(&self) -> usize;
+    fn check(&self, guess: &str, level: Difficulty) -> Result<(), Violation>;
+    // the board `:hint` works on
+    fn hinted(&self) -> &Game;
+    fn share(&self, hard: bool, high_contrast: bool) -> String;
+}
 
-mod words;
+impl Board for Game {
+    fn word_len(&self) -> usize { Game::word_len(self) }
+    fn check(&self, guess: &str, level: Difficulty) -> Result<(), Violation> { Game::check(self, guess, level) }
+    fn hinted(&self) -> &Game { self }
+    fn share(&self, hard: bool, high_contrast: bool) -> String { Game::share(self, hard, high_contrast) }
+}
 
-mod builtin_words;
+impl Board for Boards {
+    fn word_len(&self) -> usize { Boards::word_len(self) }
+    fn check(&self, guess: &str, level: Difficulty) -> Result<(), Violation> { Boards::check(self, guess, level) }
+    fn hinted(&self) -> &Game { self.boards().iter().find(|g| !g.won()).unwrap() }
+    fn share(&self, hard: bool, high_contrast: bool) -> String { Boards::share(self, hard, high_contrast) }
+}
 
-fn read_word_hinted(args: &Args, game: &Game, words: Option<&HashSet<String>>) -> String {
+// <hint>: word lists for the solver, enables `:hint` in tty mode
+// `:share` shows the results so far in tty mode
+// None once the input ends, i.e. the player quits
+fn read_word_hinted(
+    args: &Args,
+    game: &impl Board,
+    words: Option<&HashSet<String>>,
+    hint: Option<&words::Words>,
+) -> Option<String> {
     loop {
-        match utils::read_word(words){
-            Ok(w) => if !args.difficult || game.hard_check(&w) {
-                break w
-            } else {
-                if args.tty {
-                    utils::warn("Please type a word according to the information you've got.");
+        let line = match utils::read_input() {
+            Ok(None) => return None,
+            Ok(Some(line)) => Ok(line),
+            Err(e) => Err(e),
+        };
+        if let (true, Some(hint), Ok(":hint")) = (args.tty, hint, line.as_deref()) {
+            let strategy = args.strategy.unwrap_or_default().strategy();
+            match solver::suggest(game.hinted(), hint, args.difficulty(), strategy.as_ref()) {
+                Some(w) => println!("Hint: try {}", console::style(w).bold()),
+                None => utils::warn("No word fits the information you've got."),
+            }
+            continue;
+        }
+        if let (true, Some(_), Ok(":share")) = (args.tty, hint, line.as_deref()) {
+            println!("{}", game.share(args.difficult, args.high_contrast));
+            continue;
+        }
+        match line.and_then(|l| utils::check_word(&l, words, game.word_len())) {
+            Ok(w) => match game.check(&w, args.difficulty()) {
+                Ok(()) => break Some(w),
+                Err(v) => if args.tty {
+                    utils::warn(&format!("Please type a word according to the information you've got: {}.", v));
+                } else if args.verbose {
+                    println!("INVALID {}", v)
                 } else { println!("INVALID") }
             },
             Err(e) => if args.tty {
-                utils::warn(&format!("{}, please type a correct 5-character word.",e));
+                utils::warn(&format!("{}, please type a correct {}-character word.", e, game.word_len()));
+            } else if args.verbose {
+                println!("INVALID {}", e)
             } else { println!("INVALID") }
         };
     }
 }
 
-/// The main function for the Wordle game, implement your own logic here
-fn main() -> Result<(), utils::ErrorT> {
-    let mut args = Args::parse();
-    args.tty = atty::is(atty::Stream::Stdout);
-    args.refine();
-    let args = args;
-
-    let words = words::Words::new(&args);
-    let mut stats = match args.state.as_ref() {
-        None => Stats::new(),
-        Some(f) => serde_json::from_str(&utils::str_from_file(f))?
+// `wordle solve`: rank openers, or let the solver play against --word
+fn solve(args: &Args, words: &words::Words, top: usize) {
+    let strategy = args.strategy.unwrap_or_default().strategy();
+    let strategy = strategy.as_ref();
+    let mut game = Game::new(args.length.unwrap(), args.rounds.unwrap());
+    let answer = match args.word.as_ref() {
+        None => {
+            for (w, rate) in solver::rank(&game, words, args.difficulty(), strategy).iter().take(top) {
+                println!("{} {:.4}", w, rate);
+            }
+            return;
+        }
+        Some(w) => w,
     };
+    game.set_answer(answer.clone());
+    while !game.ended() {
+        let guess = match solver::suggest(&game, words, args.difficulty(), strategy) {
+            Some(w) => w.to_string(),
+            None => break,
+        };
+        game.guess(guess.clone());
+        println!("{} {}", guess, game.show_patterns().last().unwrap());
+    }
+    if game.won() {
+        println!("CORRECT {}", game.rounds());
+    } else {
+        println!("FAILED {}", answer);
+    }
+}
+
+// `wordle assist`: suggest guesses from colors observed in another game
+fn assist(args: &Args, words: &words::Words) -> Result<(), utils::ErrorT> {
+    let strategy = args.strategy.unwrap_or_default().strategy();
+    let mut game = Game::new(args.length.unwrap(), args.rounds.unwrap());
     if args.tty {
-        println!("Welcome to {}!", console::style("wordle").blink().blue());
+        println!("Type the guess and its colors (e.g. CRANE RYRRG), or only the colors if you used the suggestion.");
+    }
+    while !game.ended() {
+        let candidates = game.candidates(words);
+        let suggestion = solver::suggest(&game, words, args.difficulty(), strategy.as_ref());
+        let suggestion = match (candidates.is_empty(), suggestion) {
+            (false, Some(w)) => w,
+            _ => {
+                if args.tty {
+                    utils::warn("No word fits the colors you've got.");
+                } else {
+                    println!("FAILED");
+                }
+                return Ok(());
+            }
+        };
+        if args.tty {
+            println!(
+                "Try {} ({} possible {})",
+                console::style(suggestion).bold(),
+                candidates.len(),
+                if candidates.len() == 1 { "answer" } else { "answers" }
+            );
+        } else {
+            println!("{} {}", suggestion, candidates.len());
+        }
+        // read a guess with its pattern
+        let (guess, pattern) = loop {
+            let line = utils::read_line()?;
+            if line.is_empty() {
+                return Ok(());
+            }
+            let (guess, pattern) = match line.split_once(' ') {
+                Some((g, p)) => (g.trim().to_ascii_uppercase(), p.trim()),
+                None => (suggestion.to_string(), line.as_str()),
+            };
+            let pattern = pattern.parse::<Pattern>();
+            match pattern {
+                Ok(p) if p.len() == game.word_len()
+                    && guess.len() == game.word_len()
+                    && guess.chars().all(|c| c.is_ascii_uppercase()) =>
+                {
+                    break (guess, p)
+                }
+                _ => {
+                    if args.tty {
+                        utils::warn(&format!(
+                            "Please type a {}-character word and its colors, like CRANE RYRRG.",
+                            game.word_len()
+                        ));
+                    } else {
+                        println!("INVALID");
+                    }
+                }
+            }
+        };
+        if game.record(guess, pattern) {
+            if args.tty {
+                println!("Solved with {} guesses!", game.rounds());
+            } else {
+                println!("CORRECT {}", game.rounds());
+            }
+        }
+    }
+    if !game.won() {
+        if args.tty {
+            utils::warn("Out of guesses.");
+        } else {
+            println!("FAILED");
+        }
     }
+    Ok(())
+}
 
-    for day in args.day.unwrap() - 1.. {
-        // Init game
-        let mut game = Game::new();
+// `wordle bench`: print guess distribution, failures and mean guesses of strategies
+fn bench(args: &Args, words: &words::Words) {
+    let kinds = match args.strategy {
+        Some(kind) => vec![kind],
+        None => StrategyKind::ALL.to_vec(),
+    };
+    for kind in kinds {
+        let (len, rounds) = (args.length.unwrap(), args.rounds.unwrap());
+        let result = solver::benchmark(words, len, rounds, args.difficulty(), kind.strategy().as_ref());
+        let distribution: Vec<String> = result
+            .distribution
+            .iter()
+            .enumerate()
+            .map(|(i, n)| format!("{}:{}", i + 1, n))
+            .collect();
+        println!(
+            "{} {} X:{} {:.4}",
+            kind.name(),
+            distribution.join(" "),
+            result.failed,
+            result.mean()
+        );
+    }
+}
+
+// how a game from the final list entry <day> is played, according to <args>
+fn meta(args: &Args, words: &words::Words, day: usize) -> Meta {
+    let mode = if args.adversarial {
+        Mode::Adversarial
+    } else if args.daily {
+        Mode::Daily
+    } else if args.random {
+        Mode::Random
+    } else {
+        Mode::Fixed
+    };
+    match mode {
+        Mode::Random | Mode::Daily => Meta::new(mode, args.seed, Some(day), words),
+        _ => Meta::new(mode, None, None, words),
+    }
+}
+
+// play one game of the day <day> (0-based), return whether it ended before the player quit
+fn play_game(args: &Args, words: &words::Words, stats: &mut Stats, day: usize) -> Result<bool, utils::ErrorT> {
+    // Init game
+    let mut game = Game::new(args.length.unwrap(), args.rounds.unwrap());
+    if args.adversarial {
+        game.set_adversarial(words.final_list.clone());
+    } else {
         let answer = if let Some(w) = args.word.as_ref() {
             w.clone()
         } else if !args.random {
             //TODO check whether the word is valid
             if args.tty {
                 utils::warn("You aren't using random mode. Please type answer first.");
-                read_word_hinted(&args, &game, Some(&words.r#final))
+                match read_word_hinted(args, &game, Some(&words.r#final), None) {
+                    Some(w) => w,
+                    None => return Ok(false),
+                }
             } else {
-                utils::read_word(Some(&words.r#final))?
+                utils::read_word(Some(&words.r#final), game.word_len())?
             }
         } else {
-            words.final_list[day as usize].to_string()
+            words.final_list[day % words.final_list.len()].to_string()
         };
         game.set_answer(answer);
+    }
+    game.set_meta(meta(args, words, day % words.final_list.len()), args.difficulty());
+    if args.daily {
+        game.set_puzzle(day);
+        let date = game.date().unwrap();
+        if args.tty {
+            println!("Daily puzzle {} of {}", day, date);
+            if stats.played(date, 1) {
+                utils::warn(&format!("You've played the puzzle of {} before, this game won't be counted.", date));
+            }
+        }
+    }
+    guess_game(args, words, stats, game)
+}
 
+// guess until <game> ends, saving it after every guess so that it can be resumed
+fn guess_game(args: &Args, words: &words::Words, stats: &mut Stats, mut game: Game) -> Result<bool, utils::ErrorT> {
+    if args.tty {
+        println!("Now, please guess the {}-character word! (type :hint for a hint, :share for your results)", game.word_len());
+    }
+    let mut win = false;
+    while !game.ended() {
+        let word = match read_word_hinted(args, &game, Some(&words.valid), Some(words)) {
+            Some(w) => w,
+            None => {
+                quit(args);
+                return Ok(false);
+            }
+        };
+        win = game.guess(word.clone());
+        // print guess result
         if args.tty {
-            println!("Now, please guess the 5-character word!");
+            let (col_pos, col_alpha) = game.show_col();
+            for (i, c) in word.chars().enumerate() {
+                print!("{}", utils::colorize_id(col_pos[i]).apply_to(c));
+            }
+            print!(" ");
+            for(i, c) in ('A'..='Z').enumerate() {
+                print!("{}", utils::colorize_id(col_alpha[i]).apply_to(c));
+            }
+            println!();
+            io::stdout().flush()?;
+        } else {
+            println!("{}", game);
+        }
+        if !game.ended() {
+            stats.set_unfinished(&game);
+            save_state(args, stats)?;
         }
-        let mut win = false;
-        for round in 0..utils::ROUNDS {
-            let word = read_word_hinted(&args, &game, Some(&words.valid));
-            win = game.guess(word.clone());
-            // print guess result
+
+        if win {
+            let round = game.rounds();
             if args.tty {
-                let (col_pos, col_alpha) = game.show_col();
+                println!("Congratulations! You made it with {} {}.",
+                    round, if round == 1 { "guess" } else { "guesses" });
+            } else {
+                println!("CORRECT {}", round);
+            }
+        }
+    }
+    if !win {
+        if args.tty {
+            println!("Sorry that you failed. The answer is {}", game.show_answer());
+        } else{
+            println!("FAILED {}", game.show_answer());
+        }
+    }
+    if args.share {
+        println!("{}", game.share(args.difficult, args.high_contrast));
+    }
+    stats.clear_unfinished();
+    if args.stats {
+        stats.store_game(game);
+        stats.print_stats(args.tty, args.rounds.unwrap(), 1, args.histogram);
+    }
+    Ok(true)
+}
+
+// answers of the <n> boards of day <day>: consecutive words of the shuffled list from <day * n>,
+// so that days don't share answers, and no word twice in a game
+fn day_answers(words: &words::Words, day: usize, n: usize) -> Vec<String> {
+    let len = words.final_list.len();
+    let mut answers: Vec<String> = vec![];
+    for i in day * n..day * n + len {
+        let word = &words.final_list[i % len];
+        if answers.len() < n && !answers.contains(word) {
+            answers.push(word.clone());
+        }
+    }
+    answers
+}
+
+// play one multi-board game of the day <day> (0-based), every guess goes to each unsolved board
+fn play_boards(args: &Args, words: &words::Words, stats: &mut Stats, day: usize) -> Result<bool, utils::ErrorT> {
+    let (len, n) = (args.length.unwrap(), args.boards.unwrap());
+    let answers = if args.random {
+        day_answers(words, day, n)
+    } else {
+        if args.tty {
+            utils::warn(&format!("You aren't using random mode. Please type {} answers first.", n));
+        }
+        let mut answers = vec![];
+        for _ in 0..n {
+            answers.push(if args.tty {
+                match read_word_hinted(args, &Game::new(len, 0), Some(&words.r#final), None) {
+                    Some(w) => w,
+                    None => return Ok(false),
+                }
+            } else {
+                utils::read_word(Some(&words.r#final), len)?
+            });
+        }
+        answers
+    };
+    let mut boards = Boards::new(len, args.rounds.unwrap(), answers);
+    // answers of day <day> start from the index <day * n>
+    boards.set_meta(meta(args, words, day * n % words.final_list.len()), args.difficulty());
+    if args.daily {
+        boards.set_puzzle(day);
+        let date = boards.boards()[0].date().unwrap();
+        if args.tty {
+            println!("Daily puzzle {} of {}", day, date);
+            if stats.played(date, n) {
+                utils::warn(&format!("You've played the puzzle of {} before, this game won't be counted.", date));
+            }
+        }
+    }
+    guess_boards(args, words, stats, boards)
+}
+
+// like `guess_game`, for several boards
+fn guess_boards(args: &Args, words: &words::Words, stats: &mut Stats, mut boards: Boards) -> Result<bool, utils::ErrorT> {
+    let (len, n) = (boards.word_len(), boards.boards().len());
+    if args.tty {
+        println!("Now, please guess the {} {}-character words! (type :hint for a hint, :share for your results)", n, len);
+    }
+    while !boards.ended() {
+        let word = match read_word_hinted(args, &boards, Some(&words.valid), Some(words)) {
+            Some(w) => w,
+            None => {
+                quit(args);
+                return Ok(false);
+            }
+        };
+        // boards solved before this guess don't show it
+        let playing: Vec<bool> = boards.boards().iter().map(|g| !g.won()).collect();
+        boards.guess(word.clone());
+        if args.tty {
+            for (game, &playing) in boards.boards().iter().zip(&playing) {
+                let (col_pos, _) = game.show_col();
                 for (i, c) in word.chars().enumerate() {
-                    print!("{}", utils::colorize_id(col_pos[i]).apply_to(c));
+                    match playing {
+                        true => print!("{}", utils::colorize_id(col_pos[i]).apply_to(c)),
+                        false => print!(" "),
+                    }
                 }
-                print!(" ");
-                for(i, c) in ('A'..='Z').enumerate() {
-                    print!("{}", utils::colorize_id(col_alpha[i]).apply_to(c));
+                print!("  ");
+            }
+            println!();
+            // every alpha once per board, colored by that board
+            for (i, c) in ('A'..='Z').enumerate() {
+                for game in boards.boards() {
+                    print!("{}", utils::colorize_id(game.show_col().1[i]).apply_to(c));
                 }
-                println!("");
-                io::stdout().flush()?;
-            } else {
+                print!(" ");
+            }
+            println!();
+            io::stdout().flush()?;
+        } else {
+            for (game, _) in boards.boards().iter().zip(&playing).filter(|(_, &p)| p) {
                 println!("{}", game);
             }
+        }
+        if !boards.ended() {
+            stats.set_unfinished_boards(&boards);
+            save_state(args, stats)?;
+        }
+    }
+    let answers: Vec<String> = boards.boards().iter().map(|g| g.show_answer().to_string()).collect();
+    if boards.won() {
+        if args.tty {
+            println!("Congratulations! You solved all {} boards with {} guesses.", n, boards.rounds());
+        } else {
+            println!("CORRECT {}", boards.rounds());
+        }
+    } else if args.tty {
+        println!("Sorry that you failed. The answers are {}", answers.join(" "));
+    } else {
+        println!("FAILED {}", answers.join(" "));
+    }
+    if args.share {
+        println!("{}", boards.share(args.difficult, args.high_contrast));
+    }
+    stats.clear_unfinished();
+    if args.stats {
+        stats.store_boards(boards);
+        stats.print_stats(args.tty, args.rounds.unwrap(), n, args.histogram);
+    }
+    Ok(true)
+}
 
-            if win {
-                if args.tty {
-                    println!("Congratulations! You made it with {} {}.",
-                        round+1, if round == 0 { "guess" } else { "guesses" });
-                } else {
-                    println!("CORRECT {}", round+1);
+// the game in progress stays in the state file when the input ends
+fn quit(args: &Args) {
+    if args.tty && args.state.is_some() {
+        println!("Your game is saved, play again with the same state file to resume it.");
+    }
+}
+
+// whether <unfinished> is the game -w or --daily asks for, any game is fine without them
+fn requested(args: &Args, unfinished: &Unfinished) -> bool {
+    let game = unfinished.first();
+    let mode = game.meta().map(|m| m.mode);
+    if let Some(w) = args.word.as_ref() {
+        mode == Some(Mode::Fixed) && unfinished.boards() == 1 && game.show_answer() == w
+    } else if args.daily {
+        mode == Some(Mode::Daily)
+            && unfinished.boards() == args.boards.unwrap()
+            && game.puzzle() == Some(args.day.unwrap() as usize - 1)
+    } else {
+        true
+    }
+}
+
+// offer to resume the game left unfinished in the state file, Y by default.
+// In non-tty mode the answer is read without a prompt, like the one for another game
+fn resume(args: &Args, stats: &mut Stats) -> Result<Option<Unfinished>, utils::ErrorT> {
+    let unfinished = match stats.unfinished() {
+        Some(u) if u.word_len() == args.length.unwrap() && requested(args, &u) => u,
+        _ => return Ok(None),
+    };
+    if args.tty {
+        println!("You have an unfinished game with {} guesses, resume it? [Y/N]", unfinished.rounds());
+    }
+    let mut line = utils::read_line()?;
+    while args.tty && line != "N" && line != "Y" && !line.is_empty() {
+        line = utils::read_line()?;
+    }
+    if line != "N" && line != "Y" && !line.is_empty() {
+        return Err(WordleError::UnexpectedAnswer {
+            prompt: "resume the unfinished game".to_string(),
+            answer: line,
+        }
+        .into());
+    }
+    if line == "N" {
+        stats.clear_unfinished();
+        return Ok(None);
+    }
+    if args.tty {
+        // show the guesses replayed so far
+        let games: Vec<&Game> = match &unfinished {
+            Unfinished::Game(game) => vec![game.as_ref()],
+            Unfinished::Boards(boards) => boards.boards().iter().collect(),
+        };
+        for (i, guess) in games[0].show_guesses().iter().enumerate() {
+            for game in games.iter() {
+                match game.show_patterns().get(i) {
+                    Some(p) => for (c, id) in guess.chars().zip(p.iter()) {
+                        print!("{}", utils::colorize_id(id).apply_to(c));
+                    },
+                    None => print!("{}", " ".repeat(guess.len())),
                 }
-                break;
+                print!("  ");
             }
+            println!();
+        }
+    }
+    Ok(Some(unfinished))
+}
+
+// `wordle archive`: list the latest <count> daily puzzles, then play the ones picked
+fn archive(args: &Args, words: &words::Words, stats: &mut Stats, count: usize) -> Result<(), utils::ErrorT> {
+    // refine() puts the latest puzzle in daily mode
+    let latest = args.day.unwrap() as usize - 1;
+    loop {
+        if args.tty {
+            println!("Type the number of a puzzle to play it, or nothing to quit.");
         }
-        if !win {
+        for puzzle in ((latest + 1).saturating_sub(count)..=latest).rev() {
+            let date = daily::format_date(daily::date_of(puzzle).unwrap());
+            let status = stats.puzzle_status(puzzle, args.boards.unwrap());
+            let text = match status {
+                PuzzleStatus::NotPlayed => "NEW".to_string(),
+                PuzzleStatus::Won(n) => format!("WON {}", n),
+                PuzzleStatus::Lost => "LOST".to_string(),
+            };
             if args.tty {
-                println!("Sorry that you failed. The answer is {}", game.show_answer());
-            } else{
-                println!("FAILED {}", game.show_answer());
+                let text = match status {
+                    PuzzleStatus::NotPlayed => console::style(text),
+                    PuzzleStatus::Won(_) => console::style(text).green(),
+                    PuzzleStatus::Lost => console::style(text).red(),
+                };
+                println!("#{} {} {}", puzzle, date, text);
+            } else {
+                println!("{} {} {}", puzzle, date, text);
             }
         }
-        if args.stats {
-            stats.store_game(game);
-            stats.print_stats(args.tty);
+        let puzzle = loop {
+            let line = utils::read_line()?;
+            if line.is_empty() {
+                return Ok(());
+            }
+            match line.trim_start_matches('#').parse::<usize>() {
+                Ok(p) if p <= latest => break p,
+                _ => if args.tty {
+                    utils::warn(&format!("Please type a puzzle number from 0 to {}.", latest));
+                } else {
+                    println!("INVALID");
+                }
+            }
+        };
+        let finished = if args.boards.unwrap() > 1 {
+            play_boards(args, words, stats, puzzle)?
+        } else {
+            play_game(args, words, stats, puzzle)?
+        };
+        if !finished {
+            return Ok(());
+        }
+        save_state(args, stats)?;
+    }
+}
+
+// a corrupt state file is moved aside with --recover, so that a fresh one can be saved
+fn load_state(args: &Args, file: &str) -> Result<Stats, WordleError> {
+    let io_err = |e: io::Error| WordleError::StateIo { file: file.to_string(), reason: e.to_string() };
+    match Stats::load(&utils::str_from_file(file).map_err(io_err)?) {
+        Ok(stats) => Ok(stats),
+        // a later build can read it, it isn't corrupt
+        Err(LoadError::Newer(version)) => Err(WordleError::StateVersion { file: file.to_string(), version }),
+        Err(e) if args.recover => {
+            let backup = format!("{}.corrupt-{}", file, time::OffsetDateTime::now_utc().unix_timestamp());
+            fs::rename(file, &backup).map_err(io_err)?;
+            eprintln!("State file {} is corrupt ({}), moved it to {} and started fresh.", file, e, backup);
+            Ok(Stats::new())
+        }
+        Err(e) => Err(WordleError::StateParse { file: file.to_string(), reason: e.to_string() }),
+    }
+}
+
+// save after every game, so that an interrupted session keeps the games played
+fn save_state(args: &Args, stats: &Stats) -> Result<(), WordleError> {
+    match args.state.as_ref() {
+        Some(file) => stats.save(file),
+        None => Ok(()),
+    }
+}
+
+// --no-repeat: the first day from <day> whose <n> answers aren't in <played>,
+// None once every answer of the final list is played
+fn unplayed_day(words: &words::Words, played: &HashSet<String>, day: usize, n: usize) -> Option<usize> {
+    (day..day + words.final_list.len()).find(|&d| day_answers(words, d, n).iter().all(|a| !played.contains(a)))
+}
+
+// play games from --day until the user stops
+fn play(args: &Args, words: &words::Words, stats: &mut Stats) -> Result<(), utils::ErrorT> {
+    if args.tty {
+        println!("Welcome to {}!", console::style("wordle").blink().blue());
+    }
+    let mut resumed = resume(args, stats)?;
+    let mut day = args.day.unwrap() as usize - 1;
+    // answers to skip, the ones of the stats and then the ones of this session
+    let mut played = if args.no_repeat { stats.answers() } else { HashSet::new() };
+    loop {
+        let finished = match resumed.take() {
+            Some(unfinished) => {
+                // the game keeps the level of hard mode it was started with
+                let mut args = args.clone();
+                match unfinished {
+                    Unfinished::Game(game) => {
+                        args.set_difficulty(game.difficulty());
+                        guess_game(&args, words, stats, *game)?
+                    }
+                    Unfinished::Boards(boards) => {
+                        args.set_difficulty(boards.boards()[0].difficulty());
+                        guess_boards(&args, words, stats, boards)?
+                    }
+                }
+            }
+            None => {
+                if args.no_repeat {
+                    let n = args.boards.unwrap();
+                    day = match unplayed_day(words, &played, day, n) {
+                        Some(d) => d,
+                        None => {
+                            // every answer was played, a new round starts from <day>
+                            let msg = "Every answer of the final set has been played, answers repeat from now on.";
+                            if args.tty {
+                                utils::warn(msg);
+                            } else {
+                                eprintln!("warning: {}", msg);
+                            }
+                            played.clear();
+                            day
+                        }
+                    };
+                    played.extend(day_answers(words, day, n));
+                }
+                day += 1;
+                if args.boards.unwrap() > 1 {
+                    play_boards(args, words, stats, day - 1)?
+                } else {
+                    play_game(args, words, stats, day - 1)?
+                }
+            }
+        };
+        if !finished {
+            break;
         }
-        // find out whether the program should continue
-        if args.word.is_none() {
+        save_state(args, stats)?;
+        // find out whether the program should continue, there's one daily puzzle a day
+        if args.word.is_none() && !args.daily {
             let mut line = utils::read_line()?;
-            while args.tty && line != "N" && line != "Y" && line != "" {
+            while args.tty && line != "N" && line != "Y" && !line.is_empty() {
                 line = utils::read_line()?;
             }
-            let line = line;
-            if line != "N" && line != "Y" && line != "" {
-                panic!("should we continue?");
+            if line != "N" && line != "Y" && !line.is_empty() {
+                return Err(WordleError::UnexpectedAnswer {
+                    prompt: "play another game".to_string(),
+                    answer: line,
+                }
+                .into());
             }
             if line == "N" {
                 break;
@@ -130,9 +691,120 @@ fn main() -> Result<(), utils::ErrorT> {
             break;
         }
     }
-    // Save state to json
-    if let Some(file) = args.state {
-        utils::str_to_file(serde_json::to_string_pretty(&stats)?.as_str(), &file);
+    Ok(())
+}
+
+// `wordle import-share`: count results pasted as share texts in stats
+fn import_share(args: &Args, stats: &mut Stats, file: &str) -> Result<(), WordleError> {
+    if args.state.is_none() {
+        return Err(WordleError::ConflictingArgs("import-share needs --state to keep the results".to_string()));
+    }
+    let text = utils::str_from_file(file)
+        .map_err(|e| WordleError::FileIo { file: file.to_string(), reason: e.to_string() })?;
+    let (mut imported, mut counted) = (0, 0);
+    for shared in share::parse(&text) {
+        match shared {
+            Ok(s) => if stats.store_shared(&s) {
+                imported += 1;
+            } else {
+                counted += 1;
+            },
+            Err(e) => if args.tty {
+                utils::warn(&format!("Skipped {}.", e));
+            } else {
+                println!("INVALID {}", e);
+            },
+        }
+    }
+    if args.tty {
+        println!("Imported {} results, skipped {} already counted.", imported, counted);
+    } else {
+        println!("IMPORTED {} {}", imported, counted);
+    }
+    Ok(())
+}
+
+// `wordle words check`: report every problem of the word lists, optionally writing normalized copies
+fn check_words(args: &Args, final_out: Option<&String>, acceptable_out: Option<&String>) -> Result<(), WordleError> {
+    let (final_set, acceptable_set) = (words::List::final_set(args)?, words::List::acceptable_set(args)?);
+    let issues = words::check(&final_set, &acceptable_set, args.length.unwrap());
+    for issue in issues.iter() {
+        match (args.tty, issue.is_error(), issue.is_note()) {
+            (true, true, _) => utils::warn(&format!("{}", issue)),
+            (true, false, false) => println!("{}", console::style(issue).yellow()),
+            (true, false, true) => println!("{}", console::style(issue).dim()),
+            (false, true, _) => println!("ERROR {}", issue),
+            (false, false, false) => println!("WARNING {}", issue),
+            (false, false, true) => println!("NOTE {}", issue),
+        }
+    }
+    for (list, out) in [(&final_set, final_out), (&acceptable_set, acceptable_out)] {
+        if let Some(out) = out {
+            utils::str_to_file_atomic(&list.normalized().join("\n"), out)
+                .map_err(|e| WordleError::FileIo { file: out.clone(), reason: e.to_string() })?;
+        }
+    }
+    let errors = issues.iter().filter(|i| i.is_error()).count();
+    let notes = issues.iter().filter(|i| i.is_note()).count();
+    if args.tty {
+        println!("{} errors, {} warnings, {} notes.", errors, issues.len() - errors - notes, notes);
+    } else {
+        println!("CHECKED {} {} {}", errors, issues.len() - errors - notes, notes);
+    }
+    match issues.iter().find(|i| i.is_error()) {
+        Some(issue) => Err(issue.to_error()),
+        None => Ok(()),
+    }
+}
+
+/// The main function for the Wordle game, implement your own logic here.
+/// Errors are printed and exit with the codes documented in `wordle::error`
+fn main() {
+    if let Err(e) = run() {
+        eprintln!("error: {}", e);
+        let code = e.downcast_ref::<WordleError>().map_or(1, |e| e.exit_code());
+        std::process::exit(code);
+    }
+}
+
+fn run() -> Result<(), utils::ErrorT> {
+    let mut args = Args::parse();
+    args.tty = atty::is(atty::Stream::Stdout);
+    args.refine()?;
+    let args = args;
+
+    if let Some(Command::Words { command: WordsCommand::Check { final_out, acceptable_out } }) = args.command.as_ref() {
+        return Ok(check_words(&args, final_out.as_ref(), acceptable_out.as_ref())?);
+    }
+    let words = words::Words::new(&args)?;
+    // `wordle words check` lists all of them
+    for warning in words.warnings.iter().take(MAX_WARNINGS) {
+        eprintln!("warning: {}", warning);
+    }
+    if words.warnings.len() > MAX_WARNINGS {
+        eprintln!(
+            "warning: {} more problems in the word lists, run `wordle words check` to see them",
+            words.warnings.len() - MAX_WARNINGS
+        );
+    }
+    match args.command {
+        Some(Command::Solve { top }) => solve(&args, &words, top),
+        Some(Command::Bench) => bench(&args, &words),
+        Some(Command::Assist) => assist(&args, &words)?,
+        _ => (),
+    }
+    if matches!(args.command, Some(Command::Solve { .. } | Command::Bench | Command::Assist)) {
+        return Ok(());
+    }
+    let mut stats = match args.state.as_ref() {
+        None => Stats::new(),
+        Some(f) => load_state(&args, f)?
+    };
+    match args.command.as_ref() {
+        Some(Command::Archive { count }) => archive(&args, &words, &mut stats, *count)?,
+        Some(Command::ImportShare { file }) => import_share(&args, &mut stats, file)?,
+        _ => play(&args, &words, &mut stats)?,
     }
+    save_state(&args, &stats)?;
     Ok(())
 }
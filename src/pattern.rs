@@ -0,0 +1,109 @@
+use std::{fmt, iter::zip, str::FromStr};
+
+use crate::game::Game;
+
+/// Colors of each position of a guess, 2 bits per position
+/// (3: G, 2: Y, 1: R), so words up to `Pattern::MAX_LEN` characters fit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Pattern {
+    code: u32,
+    len: u8,
+}
+
+impl Pattern {
+    pub const MAX_LEN: usize = 16;
+
+    // every position colored R
+    fn new(len: usize) -> Pattern {
+        assert!(
+            len <= Self::MAX_LEN,
+            "words longer than {} aren't supported",
+            Self::MAX_LEN
+        );
+        let mut pattern = Pattern {
+            code: 0,
+            len: len as u8,
+        };
+        (0..len).for_each(|i| pattern.set(i, 1));
+        pattern
+    }
+    fn set(&mut self, pos: usize, id: i8) {
+        self.code &= !(3 << (2 * pos));
+        self.code |= (id as u32) << (2 * pos);
+    }
+
+    pub fn len(&self) -> usize {
+        self.len as usize
+    }
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+    /// color id of position <pos>
+    pub fn get(&self, pos: usize) -> i8 {
+        assert!(pos < self.len());
+        (self.code >> (2 * pos) & 3) as i8
+    }
+    pub fn iter(&self) -> impl Iterator<Item = i8> + '_ {
+        (0..self.len()).map(|i| self.get(i))
+    }
+    /// packed form, unique among patterns of the same length
+    pub fn code(&self) -> u32 {
+        self.code
+    }
+    /// whether every position is G
+    pub fn solved(&self) -> bool {
+        self.iter().all(|id| id == 3)
+    }
+}
+
+/// Color <guess> against <answer>. Duplicated letters are colored from left
+/// to right after greens, e.g. guessing LEVEL with answer HELLO gives YGRRY.
+pub fn score(answer: &str, guess: &str) -> Pattern {
+    assert!(answer.len() == guess.len());
+    let mut pattern = Pattern::new(guess.len());
+    // count letters of answer that aren't guessed right
+    let mut cnt_alpha = [0i8; 26];
+    for (i, (ca, cg)) in zip(answer.bytes(), guess.bytes()).enumerate() {
+        if ca == cg {
+            pattern.set(i, Game::color2id('G'));
+        } else {
+            cnt_alpha[Game::alpha2id(ca as char)] += 1;
+        }
+    }
+    for (i, (ca, cg)) in zip(answer.bytes(), guess.bytes()).enumerate() {
+        let alpha_id = Game::alpha2id(cg as char);
+        if ca != cg && cnt_alpha[alpha_id] > 0 {
+            cnt_alpha[alpha_id] -= 1;
+            pattern.set(i, Game::color2id('Y'));
+        }
+    }
+    pattern
+}
+
+impl fmt::Display for Pattern {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s: String = self.iter().map(Game::id2color).collect();
+        write!(f, "{}", s)
+    }
+}
+
+/// Parse a pattern like `GYRRG`, where `X` is accepted as a synonym of `R`
+impl FromStr for Pattern {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.to_ascii_uppercase();
+        if s.len() > Self::MAX_LEN {
+            return Err(format!("pattern {} is too long", s));
+        }
+        let mut pattern = Pattern::new(s.len());
+        for (i, c) in s.chars().enumerate() {
+            match c {
+                'G' | 'Y' | 'R' => pattern.set(i, Game::color2id(c)),
+                'X' => (),
+                _ => return Err(format!("unknown color {} in pattern {}", c, s)),
+            }
+        }
+        Ok(pattern)
+    }
+}
@@ -0,0 +1,152 @@
+//! Parsing results shared as text, like `Game::share` prints them:
+//!
+//! ```text
+//! Wordle 1,234 4/6*
+//!
+//! ⬛🟨⬛⬛⬛
+//! ⬛⬛🟩🟨⬛
+//! 🟨🟩🟩⬛⬛
+//! 🟩🟩🟩🟩🟩
+//! ```
+
+use crate::daily;
+use crate::pattern::Pattern;
+
+/// A result found in a share text, the guesses themselves are unknown
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Shared {
+    pub puzzle: usize,
+    /// number of guesses, None if the game was lost
+    pub score: Option<usize>,
+    /// guess budget, 0 for unlimited
+    pub rounds: usize,
+    pub hard: bool,
+    pub patterns: Vec<Pattern>,
+}
+
+// color of a square, in any of the palettes and themes
+fn square2color(c: char) -> Option<char> {
+    match c {
+        '\u{1F7E9}' | '\u{1F7E7}' => Some('G'),
+        '\u{1F7E8}' | '\u{1F7E6}' => Some('Y'),
+        '\u{2B1B}' | '\u{2B1C}' => Some('R'),
+        _ => None,
+    }
+}
+
+fn parse_row(line: &str) -> Option<Pattern> {
+    let colors: Option<String> = line.trim().chars().map(square2color).collect();
+    match colors {
+        Some(colors) if !colors.is_empty() => colors.parse().ok(),
+        _ => None,
+    }
+}
+
+// `Wordle 1,234 4/6*` gives everything but the patterns,
+// None if <line> doesn't look like a header at all
+fn parse_header(line: &str) -> Option<Result<Shared, String>> {
+    // chat logs may put a timestamp or a name first
+    let rest = &line[line.find("Wordle ")? + "Wordle ".len()..];
+    let (number, result) = rest.trim().rsplit_once(' ')?;
+    if !result.contains('/') {
+        return None;
+    }
+    let err = || format!("can't read the result in \"{}\"", rest.trim());
+    // thousands may be separated by commas, dots or spaces
+    let number: String = number.chars().filter(|c| !",. ".contains(*c)).collect();
+    // a puzzle so far ahead has no date to count it on
+    let puzzle = match number.parse() {
+        Ok(p) if daily::date_of(p).is_some() => p,
+        _ => return Some(Err(err())),
+    };
+    let (result, hard) = match result.strip_suffix('*') {
+        Some(r) => (r, true),
+        None => (result, false),
+    };
+    let (score, rounds) = match result.split_once('/') {
+        Some(x) => x,
+        None => return Some(Err(err())),
+    };
+    let score = match score {
+        "X" | "x" => None,
+        s => match s.parse() {
+            Ok(s) => Some(s),
+            Err(_) => return Some(Err(err())),
+        },
+    };
+    match rounds.parse() {
+        Ok(rounds) => Some(Ok(Shared {
+            puzzle,
+            score,
+            rounds,
+            hard,
+            patterns: vec![],
+        })),
+        Err(_) => Some(Err(err())),
+    }
+}
+
+/// Find every share text in <text>, e.g. a chat log, skipping any other line.
+/// Share texts whose grid doesn't match the header are errors, with their line number
+pub fn parse(text: &str) -> Vec<Result<Shared, String>> {
+    let lines: Vec<&str> = text.lines().collect();
+    let mut results = vec![];
+    let mut i = 0;
+    while i < lines.len() {
+        let header = match parse_header(lines[i]) {
+            None => {
+                i += 1;
+                continue;
+            }
+            Some(h) => h.map_err(|e| format!("line {}: {}", i + 1, e)),
+        };
+        let header_line = i + 1;
+        i += 1;
+        // the grid may be separated from the header by blank lines
+        while i < lines.len() && lines[i].trim().is_empty() {
+            i += 1;
+        }
+        let mut patterns = vec![];
+        while let Some(p) = lines.get(i).and_then(|l| parse_row(l)) {
+            patterns.push(p);
+            i += 1;
+        }
+        let shared = header.and_then(|shared| {
+            let err = |msg: &str| Err(format!("line {}: {}", header_line, msg));
+            let solved = patterns.last().map(|p| p.solved());
+            if patterns.is_empty() {
+                return err("no grid after the header");
+            }
+            if patterns.iter().any(|p| p.len() != patterns[0].len()) {
+                return err("rows of the grid have different lengths");
+            }
+            match shared.score {
+                Some(s) if s != patterns.len() => Err(format!(
+                    "line {}: the grid has {} rows for a score of {}",
+                    header_line,
+                    patterns.len(),
+                    s
+                )),
+                Some(_) if solved != Some(true) => {
+                    err("the grid doesn't end with the winning guess")
+                }
+                None if solved == Some(true) => {
+                    err("the grid ends with the answer but X is scored")
+                }
+                // a lost game used up every guess
+                None if shared.rounds != 0 && patterns.len() != shared.rounds => Err(format!(
+                    "line {}: the grid has {} rows for X out of {}",
+                    header_line,
+                    patterns.len(),
+                    shared.rounds
+                )),
+                _ if shared.rounds != 0 && patterns.len() > shared.rounds => {
+                    err("the grid has more rows than guesses allowed")
+                }
+                _ => Ok(Shared { patterns, ..shared }),
+            }
+        });
+        results.push(shared);
+    }
+    results
+}
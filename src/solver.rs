@@ -0,0 +1,259 @@
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    game::{Difficulty, Game},
+    pattern::{self, Pattern},
+    words::Words,
+};
+
+/// A way to rate guesses against the answers still possible
+pub trait Strategy {
+    /// Rate <guess>, higher is better
+    fn rate(&self, guess: &str, candidates: &[&str]) -> f64;
+
+    /// Rate every word in <guesses>, override it to share work among guesses
+    fn rate_all(&self, guesses: &[&str], candidates: &[&str]) -> Vec<f64> {
+        guesses.iter().map(|g| self.rate(g, candidates)).collect()
+    }
+
+    /// Whether only possible answers should be guessed
+    fn candidates_only(&self) -> bool {
+        false
+    }
+}
+
+/// Maximize expected information
+pub struct MaxEntropy;
+/// Minimize the number of answers left in the worst case
+pub struct Minimax;
+/// Minimize the expected number of answers left
+pub struct ExpectedRemaining;
+/// Guess the possible answer made of the most common letters
+pub struct GreedyFrequency;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum StrategyKind {
+    #[default]
+    Entropy,
+    Minimax,
+    ExpectedRemaining,
+    Frequency,
+}
+
+impl StrategyKind {
+    pub const ALL: [StrategyKind; 4] = [
+        StrategyKind::Entropy,
+        StrategyKind::Minimax,
+        StrategyKind::ExpectedRemaining,
+        StrategyKind::Frequency,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StrategyKind::Entropy => "entropy",
+            StrategyKind::Minimax => "minimax",
+            StrategyKind::ExpectedRemaining => "expected-remaining",
+            StrategyKind::Frequency => "frequency",
+        }
+    }
+    pub fn strategy(&self) -> Box<dyn Strategy> {
+        match self {
+            StrategyKind::Entropy => Box::new(MaxEntropy),
+            StrategyKind::Minimax => Box::new(Minimax),
+            StrategyKind::ExpectedRemaining => Box::new(ExpectedRemaining),
+            StrategyKind::Frequency => Box::new(GreedyFrequency),
+        }
+    }
+}
+
+// size of each group of candidates sharing the same pattern,
+// sorted so that float sums over them don't depend on hash order
+fn buckets(guess: &str, candidates: &[&str]) -> impl Iterator<Item = usize> {
+    let mut buckets = HashMap::<u32, usize>::new();
+    for answer in candidates {
+        *buckets
+            .entry(pattern::score(answer, guess).code())
+            .or_insert(0) += 1;
+    }
+    let mut sizes: Vec<usize> = buckets.into_values().collect();
+    sizes.sort_unstable();
+    sizes.into_iter()
+}
+
+/// Expected information (in bits) revealed by <guess>,
+/// assuming every word in <candidates> is equally likely to be the answer
+pub fn entropy(guess: &str, candidates: &[&str]) -> f64 {
+    let total = candidates.len() as f64;
+    buckets(guess, candidates)
+        .map(|n| {
+            let p = n as f64 / total;
+            -p * p.log2()
+        })
+        .sum()
+}
+
+impl Strategy for MaxEntropy {
+    fn rate(&self, guess: &str, candidates: &[&str]) -> f64 {
+        entropy(guess, candidates)
+    }
+}
+
+impl Strategy for Minimax {
+    fn rate(&self, guess: &str, candidates: &[&str]) -> f64 {
+        -(buckets(guess, candidates).max().unwrap_or(0) as f64)
+    }
+}
+
+impl Strategy for ExpectedRemaining {
+    fn rate(&self, guess: &str, candidates: &[&str]) -> f64 {
+        // nothing is left whatever the guess
+        if candidates.is_empty() {
+            return 0f64;
+        }
+        let total = candidates.len() as f64;
+        -buckets(guess, candidates)
+            .map(|n| (n * n) as f64)
+            .sum::<f64>()
+            / total
+    }
+}
+
+impl GreedyFrequency {
+    // how many candidates contain each alpha
+    fn frequency(candidates: &[&str]) -> [usize; 26] {
+        let mut freq = [0usize; 26];
+        for w in candidates {
+            let alphas: HashSet<u8> = w.bytes().collect();
+            alphas
+                .into_iter()
+                .for_each(|c| freq[(c - b'A') as usize] += 1);
+        }
+        freq
+    }
+    fn rate_freq(guess: &str, freq: &[usize; 26]) -> f64 {
+        let alphas: HashSet<u8> = guess.bytes().collect();
+        alphas
+            .into_iter()
+            .map(|c| freq[(c - b'A') as usize] as f64)
+            .sum()
+    }
+}
+
+impl Strategy for GreedyFrequency {
+    fn rate(&self, guess: &str, candidates: &[&str]) -> f64 {
+        Self::rate_freq(guess, &Self::frequency(candidates))
+    }
+    fn rate_all(&self, guesses: &[&str], candidates: &[&str]) -> Vec<f64> {
+        let freq = Self::frequency(candidates);
+        guesses.iter().map(|g| Self::rate_freq(g, &freq)).collect()
+    }
+    fn candidates_only(&self) -> bool {
+        true
+    }
+}
+
+/// Rank acceptable words by <strategy> over the answers still possible in <game>,
+/// best first. Possible answers win ties, since they may end the game at once.
+/// In hard modes, only words passing `Game::check` are ranked.
+pub fn rank<'a>(
+    game: &Game,
+    words: &'a Words,
+    level: Difficulty,
+    strategy: &dyn Strategy,
+) -> Vec<(&'a str, f64)> {
+    let candidates = game.candidates(words);
+    let possible: HashSet<&str> = candidates.iter().copied().collect();
+    let guesses: Vec<&str> = words
+        .valid
+        .iter()
+        .map(|w| w.as_str())
+        .filter(|w| w.len() == game.word_len())
+        .filter(|w| game.check(w, level).is_ok())
+        .filter(|w| !strategy.candidates_only() || possible.contains(w))
+        .collect();
+    let rates = strategy.rate_all(&guesses, &candidates);
+    let mut ranked: Vec<(&str, f64, bool)> = guesses
+        .into_iter()
+        .zip(rates)
+        .map(|(w, r)| (w, r, possible.contains(w)))
+        .collect();
+    ranked.sort_by(|(w1, r1, c1), (w2, r2, c2)| r2.total_cmp(r1).then(c2.cmp(c1)).then(w1.cmp(w2)));
+    // only possible answers left? guess one of them
+    if !possible.is_empty() && possible.len() <= 2 {
+        ranked.retain(|(_, _, c)| *c);
+    }
+    ranked.into_iter().map(|(w, r, _)| (w, r)).collect()
+}
+
+/// The best next guess for <game>, if any word is left
+pub fn suggest<'a>(
+    game: &Game,
+    words: &'a Words,
+    level: Difficulty,
+    strategy: &dyn Strategy,
+) -> Option<&'a str> {
+    rank(game, words, level, strategy).first().map(|(w, _)| *w)
+}
+
+/// Result of solving every word in a final list
+#[derive(Debug, Default)]
+pub struct Benchmark {
+    /// `distribution[i]`: games won with i+1 guesses
+    pub distribution: Vec<usize>,
+    pub failed: usize,
+}
+
+impl Benchmark {
+    /// average guesses of games won
+    pub fn mean(&self) -> f64 {
+        let (games, guesses) = self
+            .distribution
+            .iter()
+            .enumerate()
+            .fold((0, 0), |acc, (i, n)| (acc.0 + n, acc.1 + (i + 1) * n));
+        if games == 0 {
+            0f64
+        } else {
+            guesses as f64 / games as f64
+        }
+    }
+}
+
+/// Let <strategy> play against every word in `words.final_list`
+/// with at most <rounds> guesses (0 for unlimited)
+pub fn benchmark(
+    words: &Words,
+    len: usize,
+    rounds: usize,
+    level: Difficulty,
+    strategy: &dyn Strategy,
+) -> Benchmark {
+    let mut bench = Benchmark::default();
+    // the strategy is deterministic, so guesses only depend on the patterns so far
+    let mut memo = HashMap::<Vec<Pattern>, Option<&str>>::new();
+    for answer in words.final_list.iter() {
+        let mut game = Game::new(len, rounds);
+        game.set_answer(answer.clone());
+        while !game.ended() {
+            let guess = *memo
+                .entry(game.show_patterns().to_vec())
+                .or_insert_with(|| suggest(&game, words, level, strategy));
+            match guess {
+                Some(w) => game.guess(w.to_string()),
+                None => break,
+            };
+        }
+        if game.won() {
+            if bench.distribution.len() < game.rounds() {
+                bench.distribution.resize(game.rounds(), 0);
+            }
+            bench.distribution[game.rounds() - 1] += 1;
+        } else {
+            bench.failed += 1;
+        }
+    }
+    bench
+}
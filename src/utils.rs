@@ -1,7 +1,11 @@
 use std::collections::HashSet;
-use std::{fs, io};
+use std::{
+    fs,
+    io::{self, Write},
+};
 
 pub const ROUNDS: usize = 6;
+// default length of words
 pub const LEN: usize = 5;
 
 pub type ErrorT = Box<dyn std::error::Error>;
@@ -13,6 +17,7 @@ pub fn apmax<T: Copy + std::cmp::Ord>(a: &mut T, b: T) {
 }
 
 // tty related
+#[cfg(feature = "cli")]
 pub fn colorize_id(id: i8) -> console::Style {
     use console::Style;
     match id {
@@ -25,23 +30,37 @@ pub fn colorize_id(id: i8) -> console::Style {
         _ => unreachable!(),
     }
 }
+#[cfg(feature = "cli")]
 pub fn warn(msg: &str) {
     println!("{}", console::style(msg).red());
 }
 
 //input related
-pub fn read_line() -> Result<String, ErrorT> {
+// None once the input ends
+pub fn read_input() -> Result<Option<String>, ErrorT> {
     let mut line = String::new();
-    io::stdin().read_line(&mut line)?;
+    if io::stdin().read_line(&mut line)? == 0 {
+        return Ok(None);
+    }
     line.truncate(line.trim().len());
-    Ok(line)
+    Ok(Some(line))
+}
+pub fn read_line() -> Result<String, ErrorT> {
+    Ok(read_input()?.unwrap_or_default())
 }
-pub fn read_word(words: Option<&HashSet<String>>) -> Result<String, ErrorT> {
-    let line = read_line()?.to_ascii_uppercase();
-    if line.len() != LEN {
+pub fn read_word(words: Option<&HashSet<String>>, len: usize) -> Result<String, ErrorT> {
+    check_word(&read_line()?, words, len)
+}
+pub fn check_word(
+    line: &str,
+    words: Option<&HashSet<String>>,
+    len: usize,
+) -> Result<String, ErrorT> {
+    let line = line.to_ascii_uppercase();
+    if line.len() != len {
         return Err(ErrorT::from(format!(
             "The length of {} isn't {}",
-            line, LEN
+            line, len
         )));
     }
     if let Some(w) = words.as_ref() {
@@ -60,15 +79,32 @@ pub fn from_arr<T: std::iter::FromIterator<String>>(arr: &[&str]) -> T {
 }
 
 // file I/O related
-pub fn str_to_file(s: &str, file: &str) {
-    fs::write(file, format!("{}\n", s)).unwrap();
-}
-pub fn str_from_file(file: &str) -> String {
-    fs::read_to_string(file).expect(format!("cannot read file {}", file).as_str())
+// write a temporary file renamed over <file> afterwards,
+// so that <file> is never left half written
+pub fn str_to_file_atomic(s: &str, file: &str) -> io::Result<()> {
+    // a name no other writer uses, even another process saving the same file
+    let mut n = 0;
+    let (tmp, mut f) = loop {
+        let tmp = format!("{}.{}-{}.tmp", file, std::process::id(), n);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&tmp)
+        {
+            Ok(f) => break (tmp, f),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => n += 1,
+            Err(e) => return Err(e),
+        }
+    };
+    let written = f
+        .write_all(format!("{}\n", s).as_bytes())
+        .and_then(|_| f.sync_all())
+        .and_then(|_| fs::rename(&tmp, file));
+    if written.is_err() {
+        let _ = fs::remove_file(&tmp);
+    }
+    written
 }
-pub fn arr_from_file<T: std::iter::FromIterator<String>>(file: &str) -> T {
-    str_from_file(file)
-        .split_whitespace()
-        .map(|x| x.to_string())
-        .collect()
+pub fn str_from_file(file: &str) -> io::Result<String> {
+    fs::read_to_string(file)
 }
@@ -10,21 +10,26 @@ use yew::{
 extern crate web_sys;
 use web_sys::HtmlInputElement;
 
-mod game;
-mod utils;
-use game::{Game, Stats};
-
-mod builtin_words;
-mod words;
-
-mod args;
-use args::Args;
+use wordle::{
+    args::Args,
+    daily,
+    game::{Game, Meta, Mode, PuzzleStatus, Stats, Summary, Unfinished},
+    solver, utils, words,
+};
 
 enum Msg {
     Input(InputEvent),
     Press(KeyboardEvent),
     Click(char),
     SwitchMode,
+    SwitchStrict,
+    SwitchAdversarial,
+    SwitchDaily,
+    SwitchArchive,
+    Play(usize),
+    SwitchContrast,
+    Share,
+    Hint,
     Refresh,
     Reset,
 }
@@ -36,12 +41,22 @@ struct App {
     stats: Stats,
     col_brd: Vec<Vec<i8>>,
     col_alpha: Vec<i8>,
+    // practice answers come from <words>, shuffled by the session seed,
+    // daily ones from the final list shuffled like for everyone else
     words: words::Words,
+    daily_list: Vec<String>,
     board: Vec<Vec<NodeRef>>,
     focus: (usize, usize),
     hint: String,
+    // daily puzzle picked from the archive, today's one if None
+    puzzle: Option<usize>,
+    // whether the archive calendar is shown
+    archive: bool,
 }
 
+// local storage key of the state, in the format of the state file
+const STORAGE_KEY: &str = "wordle-state";
+
 const KEYBOARD_0: [char; 10] = ['Q', 'W', 'E', 'R', 'T', 'Y', 'U', 'I', 'O', 'P'];
 const KEYBOARD_1: [char; 9] = ['A', 'S', 'D', 'F', 'G', 'H', 'J', 'K', 'L'];
 const KEYBOARD_2: [char; 7] = ['Z', 'X', 'C', 'V', 'B', 'N', 'M'];
@@ -56,6 +71,26 @@ fn id2background(id: i8) -> &'static str {
     }
 }
 
+// copy <text> to the clipboard, false if it's unavailable
+#[cfg(web_sys_unstable_apis)]
+fn copy(text: &str) -> bool {
+    match web_sys::window().and_then(|w| w.navigator().clipboard()) {
+        Some(clipboard) => {
+            // the promise is dropped, copying doesn't need to be awaited
+            let _ = clipboard.write_text(text);
+            true
+        }
+        None => false,
+    }
+}
+
+// web-sys only has the clipboard API with --cfg=web_sys_unstable_apis,
+// the results are shown to be copied by hand instead
+#[cfg(not(web_sys_unstable_apis))]
+fn copy(_text: &str) -> bool {
+    false
+}
+
 #[derive(Properties, PartialEq)]
 pub struct KeybrButtonProps {
     pub onclick: Callback<MouseEvent>,
@@ -90,12 +125,12 @@ impl App {
         elm.focus().unwrap();
     }
     fn focus_next(&mut self, enter: bool) {
-        if self.focus.1 < utils::LEN - 1 {
+        if self.focus.1 < self.game.word_len() - 1 {
             self.focus.1 += 1;
         } else if enter == true {
             self.focus.1 = 0;
             self.focus.0 += 1;
-            if self.focus.0 == utils::ROUNDS {
+            if self.focus.0 == self.board.len() {
                 return;
             }
         }
@@ -109,10 +144,100 @@ impl App {
         return self.game.ended() || (row, col) != self.focus;
     }
     fn postproc(&mut self) {
+        self.stats.clear_unfinished();
         self.stats.store_game(self.game.clone());
+        self.save();
+    }
+
+    fn storage() -> Option<web_sys::Storage> {
+        web_sys::window()?.local_storage().ok()?
+    }
+    fn load() -> Stats {
+        let json = match Self::storage().and_then(|s| s.get_item(STORAGE_KEY).ok()?) {
+            None => return Stats::new(),
+            Some(json) => json,
+        };
+        Stats::load(&json).unwrap_or_else(|e| {
+            log::warn!("can't read the stored state, starting fresh: {}", e);
+            Stats::new()
+        })
+    }
+    fn save(&self) {
+        let json = match serde_json::to_string(&self.stats) {
+            Ok(json) => json,
+            Err(e) => return log::warn!("failed to serialize state: {}", e),
+        };
+        match Self::storage().map(|s| s.set_item(STORAGE_KEY, &json)) {
+            Some(Ok(())) => (),
+            _ => log::warn!("failed to store state"),
+        }
+    }
+    // continue the game left unfinished when the page was closed, if any
+    fn resume(&mut self) -> bool {
+        let game = match self.stats.unfinished() {
+            Some(Unfinished::Game(game))
+                if game.word_len() == self.game.word_len() && game.rounds() < self.board.len() =>
+            {
+                game
+            }
+            _ => return false,
+        };
+        self.args.set_difficulty(game.difficulty());
+        self.args.adversarial = false;
+        self.args.daily = game.puzzle().is_some();
+        self.puzzle = game.puzzle();
+        for (row, pattern) in self.col_brd.iter_mut().zip(game.show_patterns()) {
+            *row = pattern.iter().collect();
+        }
+        self.col_alpha = game.show_col().1.clone();
+        self.focus = (game.rounds(), 0);
+        self.game = *game;
+        log::info!("game resumed: {} guesses", self.game.rounds());
+        true
+    }
+    // type the guesses of a resumed game into the board
+    fn fill_board(&self) {
+        for (row, guess) in self.board.iter().zip(self.game.show_guesses()) {
+            for (node, c) in row.iter().zip(guess.chars()) {
+                if let Some(elm) = node.cast::<HtmlInputElement>() {
+                    elm.set_value(&c.to_string());
+                }
+            }
+        }
     }
 
+    fn latest_puzzle() -> usize {
+        daily::puzzle_number(daily::today()).unwrap_or(0)
+    }
+    // the last four weeks of daily puzzles, colored by how they went
+    fn calendar(&self, ctx: &Context<Self>) -> Html {
+        let latest = Self::latest_puzzle();
+        ((latest + 1).saturating_sub(28)..=latest)
+            .rev()
+            .map(|puzzle| {
+                let date = daily::date_of(puzzle).unwrap();
+                let (col, status) = match self.stats.puzzle_status(puzzle, 1) {
+                    PuzzleStatus::NotPlayed => (0, "not played".to_string()),
+                    PuzzleStatus::Won(n) => (3, format!("won in {}", n)),
+                    PuzzleStatus::Lost => (1, "lost".to_string()),
+                };
+                html! {
+                    <button class={"keybr-button"}
+                    style={format!("background: {}", id2background(col))}
+                    title={format!("#{} {}: {}", puzzle, daily::format_date(date), status)}
+                    onclick={ctx.link().callback(move |_: MouseEvent| Msg::Play(puzzle))}>
+                    { date.day() }
+                    </button>
+                }
+            })
+            .collect::<Html>()
+    }
     pub fn start(&mut self) {
+        // a new game drops the one left unfinished
+        if self.game.rounds() > 0 && !self.game.ended() {
+            self.stats.clear_unfinished();
+            self.save();
+        }
         // clear colors
         self.col_alpha.iter_mut().for_each(|col| *col = 0);
         self.col_brd.iter_mut().for_each(|row| {
@@ -129,9 +254,31 @@ impl App {
         // ensure focus
         self.focus = (0, 0);
 
-        if let Some(w) = self.args.word.as_ref() {
+        if self.args.adversarial {
+            self.game = Game::new(self.args.length.unwrap(), self.args.rounds.unwrap());
+            self.game.set_adversarial(self.words.final_list.clone());
+            let meta = Meta::new(Mode::Adversarial, None, None, &self.words);
+            self.game.set_meta(meta, self.args.difficulty());
+            log::info!("game start: adversarial");
+            return;
+        }
+        let meta = if let Some(w) = self.args.word.as_ref() {
             log::info!("answer copied from {}", w);
             self.game.set_answer(w.clone());
+            Meta::new(Mode::Fixed, None, None, &self.words)
+        } else if self.args.daily {
+            let number = self.puzzle.unwrap_or_else(Self::latest_puzzle);
+            self.game = Game::new(self.args.length.unwrap(), self.args.rounds.unwrap());
+            // the list is long enough for years of puzzles, wrap around anyway
+            let list = &self.daily_list;
+            self.game.set_answer(list[number % list.len()].clone());
+            self.game.set_puzzle(number);
+            Meta::new(
+                Mode::Daily,
+                Some(daily::SEED),
+                Some(number % list.len()),
+                &self.words,
+            )
         } else {
             let d = match self.args.day {
                 None => {
@@ -140,15 +287,23 @@ impl App {
                 }
                 Some(d) => d,
             };
-            self.game = Game::new();
-            let answer = self.words.final_list[d as usize].clone();
+            self.game = Game::new(self.args.length.unwrap(), self.args.rounds.unwrap());
+            let list = &self.words.final_list;
+            let answer = list[d as usize % list.len()].clone();
             self.game.set_answer(answer);
             self.args.day = Some(d + 1);
-        }
+            Meta::new(
+                Mode::Random,
+                self.args.seed,
+                Some(d as usize % list.len()),
+                &self.words,
+            )
+        };
+        self.game.set_meta(meta, self.args.difficulty());
         log::info!("game start: answer {}", self.game.show_answer());
     }
     pub fn insert(&mut self, _c: char) {
-        if self.focus.1 != utils::LEN - 1 {
+        if self.focus.1 != self.game.word_len() - 1 {
             self.focus_next(false);
         }
     }
@@ -178,7 +333,7 @@ impl App {
             .to_ascii_uppercase();
         // return if invalid
         log::info!("submit guess: {}", guess);
-        if guess.len() < utils::LEN {
+        if guess.len() < self.game.word_len() {
             self.hint = format!("Not enough letters: {}", guess);
             return;
         }
@@ -187,8 +342,8 @@ impl App {
             self.hint = format!("{} isn't a word.", guess);
             return;
         }
-        if self.args.difficult && !self.game.hard_check(&guess) {
-            self.hint = format!("{}: must use information revealed before.", guess);
+        if let Err(v) = self.game.check(&guess, self.args.difficulty()) {
+            self.hint = format!("{}: {}.", guess, v);
             return;
         }
         self.game.guess(guess);
@@ -201,6 +356,8 @@ impl App {
         if self.game.ended() {
             self.postproc();
         } else {
+            self.stats.set_unfinished(&self.game);
+            self.save();
             self.focus_next(true);
         }
     }
@@ -228,9 +385,10 @@ impl Component for App {
     type Properties = ();
 
     fn create(_ctx: &Context<Self>) -> Self {
-        let mut args: args::Args = Default::default();
+        let mut args: Args = Default::default();
         // Set random mode to true and add random seed since this is a web app
         args.random = true;
+        args.daily = true;
         if args.seed.is_none() {
             let mut s = [0u8];
             if let Err(e) = getrandom::getrandom(s.as_mut_slice()) {
@@ -238,24 +396,44 @@ impl Component for App {
             }
             args.seed = Some(s[0].into());
         }
+        // builtin word lists only contain 5-character words
+        let len = *args.length.get_or_insert(utils::LEN);
+        // the board has a fixed number of rows, so unlimited rounds are not supported
+        let rounds = *args.rounds.get_or_insert(utils::ROUNDS);
+        // a single board, stats are still kept apart from multi-board games of the cli
+        args.boards.get_or_insert(1);
         let mut app = Self {
-            game: Game::new(),
-            stats: Default::default(),
-            board: (0..utils::ROUNDS)
-                .map(|_| (0..utils::LEN).map(|_| NodeRef::default()).collect())
+            game: Game::new(len, rounds),
+            stats: Self::load(),
+            board: (0..rounds)
+                .map(|_| (0..len).map(|_| NodeRef::default()).collect())
                 .collect(),
-            words: words::Words::new(&args),
+            // builtin word lists are always valid
+            words: words::Words::new(&args).unwrap(),
+            daily_list: words::Words::new(&Args {
+                seed: Some(daily::SEED),
+                ..args.clone()
+            })
+            .unwrap()
+            .final_list,
             args: args,
-            col_brd: vec![vec![0i8; utils::LEN]; utils::ROUNDS],
+            col_brd: vec![vec![0i8; len]; rounds],
             col_alpha: vec![0i8; 26],
             focus: (0, 0),
             hint: String::new(),
+            puzzle: None,
+            archive: false,
         };
-        app.start();
+        if !app.resume() {
+            app.start();
+        }
         app
     }
 
     fn rendered(&mut self, _ctx: &Context<Self>, first_render: bool) {
+        if first_render {
+            self.fill_board();
+        }
         self.apply_focus();
     }
 
@@ -298,17 +476,72 @@ impl Component for App {
                         elm.set_value(&c.to_string());
                         self.insert(c);
                     } else {
-                        assert!(self.focus.1 == utils::LEN - 1);
+                        assert!(self.focus.1 == self.game.word_len() - 1);
                     }
                 }
             }
             Msg::SwitchMode => {
                 if self.game.rounds() == 0 || self.game.ended() || self.args.difficult {
                     self.args.difficult ^= true;
+                    self.args.strict &= self.args.difficult;
                 } else {
                     unreachable!();
                 }
             }
+            Msg::SwitchStrict => {
+                if self.game.rounds() == 0 || self.game.ended() || self.args.strict {
+                    self.args.strict ^= true;
+                    self.args.difficult |= self.args.strict;
+                } else {
+                    unreachable!();
+                }
+            }
+            Msg::SwitchAdversarial => {
+                self.args.adversarial ^= true;
+                // nothing guessed yet, so the switch can apply to this game
+                if self.game.rounds() == 0 {
+                    self.start();
+                }
+            }
+            Msg::SwitchDaily => {
+                self.args.daily ^= true;
+                self.puzzle = None;
+                if self.game.rounds() == 0 {
+                    self.start();
+                }
+            }
+            Msg::SwitchArchive => self.archive ^= true,
+            Msg::SwitchContrast => self.args.high_contrast ^= true,
+            Msg::Share => {
+                let text = self
+                    .game
+                    .share(self.args.difficult, self.args.high_contrast);
+                if copy(&text) {
+                    self.hint = "Results copied to clipboard.".to_string();
+                } else {
+                    log::warn!("clipboard unavailable");
+                    self.hint = text;
+                }
+            }
+            Msg::Play(puzzle) => {
+                self.args.daily = true;
+                self.puzzle = Some(puzzle);
+                self.archive = false;
+                self.start();
+            }
+            Msg::Hint => {
+                let strategy = self.args.strategy.unwrap_or_default().strategy();
+                let hint = solver::suggest(
+                    &self.game,
+                    &self.words,
+                    self.args.difficulty(),
+                    strategy.as_ref(),
+                );
+                self.hint = match hint {
+                    Some(w) => format!("Hint: try {}", w),
+                    None => "No word fits the information you've got.".to_string(),
+                };
+            }
             Msg::Refresh => (),
             Msg::Reset => self.start(),
         }
@@ -342,7 +575,12 @@ impl Component for App {
         let keybr_r1 = keyarr2html(&KEYBOARD_1, &self.col_alpha, ctx);
         let keybr_r2 = keyarr2html(&KEYBOARD_2, &self.col_alpha, ctx);
         // Stats helper
-        let (win_rounds, lose_rounds, avg_guesses) = self.stats.feed_stats();
+        let (win_rounds, lose_rounds, avg_guesses) = self
+            .stats
+            .feed_stats(self.args.rounds.unwrap(), self.args.boards.unwrap());
+        let summary = self
+            .stats
+            .feed_summary(self.args.rounds.unwrap(), self.args.boards.unwrap());
         let w_list = self.stats.feed_words();
 
         html! {
@@ -360,7 +598,40 @@ impl Component for App {
                 />
                 <label for="hardmode" title={hard_invld_msg}>{"Hard mode"}</label>
             }
+            if self.game.rounds() == 0 || self.game.ended() || self.args.strict {
+                <input type="checkbox" id="strictmode" checked={self.args.strict} oninput={
+                    ctx.link().callback(|_| Msg::SwitchStrict)
+                }/>
+                <label for="strictmode">{"Strict"}</label>
+            } else {
+                <input type="checkbox" id="strictmode" checked={self.args.strict}
+                    disabled={true} title={hard_invld_msg}
+                />
+                <label for="strictmode" title={hard_invld_msg}>{"Strict"}</label>
+            }
+            <input type="checkbox" id="adversarial" checked={self.args.adversarial} oninput={
+                ctx.link().callback(|_| Msg::SwitchAdversarial)
+            } title={"Takes effect from the next game once you have guessed."}/>
+            <label for="adversarial">{"Adversarial"}</label>
+            <input type="checkbox" id="daily" checked={self.args.daily} oninput={
+                ctx.link().callback(|_| Msg::SwitchDaily)
+            } title={"Takes effect from the next game once you have guessed."}/>
+            <label for="daily">{"Daily"}</label>
+            <button class={"keybr-button"} onclick={
+                ctx.link().callback(|_: MouseEvent| Msg::SwitchArchive)
+            }>{"Archive"}</button>
+            if !self.game.ended() {
+                <button class={"keybr-button"} onclick={
+                    ctx.link().callback(|_: MouseEvent| Msg::Hint)
+                }>{"Hint"}</button>
+            }
             </p>
+            if self.archive {
+                <div class={"keybr_row"}>{ self.calendar(ctx) }</div>
+            }
+            if let (Some(puzzle), Some(date)) = (self.game.puzzle(), self.game.date()) {
+                <p>{format!("Puzzle #{} of {}", puzzle, date)}</p>
+            }
             // Dashboard
             <div class={"board"} onclick={refresh}> {
                 self.board.iter().enumerate().map(|(row, x)| html! {
@@ -383,11 +654,19 @@ impl Component for App {
                 }).collect::<Html>()
             }
             </div>
-            // Reset button
+            // Reset and share buttons
             if self.game.ended() {
                 <button class={"keybr-button"} onclick={
                     ctx.link().callback(|_: MouseEvent| Msg::Reset)
                 }>{"Restart!"}</button>
+                <button class={"keybr-button"} onclick={
+                    ctx.link().callback(|_: MouseEvent| Msg::Share)
+                }>{"Share"}</button>
+                <input type="checkbox" id="contrast" checked={self.args.high_contrast} oninput={
+                    ctx.link().callback(|_| Msg::SwitchContrast)
+                }/>
+                <label for="contrast">{"High contrast"}</label>
+                <p style="white-space:pre">{format!("{} ", self.hint)}</p>
             }
             if !self.game.ended(){
                 // Hint board
@@ -418,6 +697,12 @@ impl Component for App {
                 <p style="margin:0.6em; color:red">{format!("Lose: {}", lose_rounds)}</p>
                 <p style="margin:0.6em">{format!("AVG guesses: {:.2}", avg_guesses)}</p>
                 </div>
+                <div style="display:inline-flex">
+                <p style="margin:0.6em">{format!("Win %: {:.0}", summary.win_rate())}</p>
+                <p style="margin:0.6em">{format!("Current streak: {}", summary.current_streak)}</p>
+                <p style="margin:0.6em">{format!("Max streak: {}", summary.max_streak)}</p>
+                </div>
+                { histogram(&summary) }
                 <p>{"Words used most:"}</p>
                 <table style="" align="center">
                 {
@@ -436,6 +721,35 @@ impl Component for App {
     }
 }
 
+// a bar per number of guesses, plus one for failed games
+fn histogram(summary: &Summary) -> Html {
+    let most = summary
+        .distribution
+        .iter()
+        .copied()
+        .chain([summary.failed])
+        .max()
+        .unwrap_or(0)
+        .max(1);
+    let bar = |label: String, n: usize, col: i8| {
+        html! {
+            <div style="display:flex; width:20em; margin:0.2em auto">
+            <span style="width:1.5em">{label}</span>
+            <span style={format!("width:{}%; min-width:1.5em; background:{}", n * 90 / most, id2background(col))}>
+            {n}
+            </span>
+            </div>
+        }
+    };
+    summary
+        .distribution
+        .iter()
+        .enumerate()
+        .map(|(i, n)| bar((i + 1).to_string(), *n, 3))
+        .chain([bar("X".to_string(), summary.failed, 1)])
+        .collect::<Html>()
+}
+
 fn main() {
     wasm_logger::init(wasm_logger::Config::default());
     yew::start_app::<App>();
@@ -1,42 +1,431 @@
-use crate::{args, builtin_words, utils};
-use rand::{prelude::SliceRandom, SeedableRng};
-use std::collections::HashSet;
+use crate::{args, builtin_words, error::WordleError, utils};
+use rand::{prelude::SliceRandom, Rng, SeedableRng};
+use serde::Deserialize;
+use std::{
+    collections::{HashMap, HashSet},
+    fmt,
+};
 
 pub struct Words {
+    /// answers by day, shuffled in random mode
     pub final_list: Vec<String>,
     pub r#final: HashSet<String>,
     pub valid: HashSet<String>,
+    /// weight and tags of the final and acceptable words
+    pub info: HashMap<String, WordInfo>,
+    /// problems of the lists that don't stop the game, except notes, see `check`
+    pub warnings: Vec<Issue>,
+}
+
+/// How random answers are drawn from the final set
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[cfg_attr(feature = "cli", derive(clap::ValueEnum))]
+#[serde(rename_all = "kebab-case")]
+pub enum Weighting {
+    /// every word once, shuffled
+    #[default]
+    Uniform,
+    /// every word once, heavier words tend to come first
+    Frequency,
+}
+
+/// Stands for the builtin list in --final-set and --acceptable-set
+pub const BUILTIN: &str = "builtin";
+
+/// What a list file may tell about a word after it, like `CRANE 0.8 common,animal`
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordInfo {
+    /// how often the word should be drawn relatively to others, 1 by default
+    pub weight: f64,
+    /// lowercased
+    pub tags: Vec<String>,
+}
+
+impl Default for WordInfo {
+    fn default() -> Self {
+        WordInfo {
+            weight: 1f64,
+            tags: vec![],
+        }
+    }
+}
+
+/// A word of a list and where it was found
+#[derive(Debug, Clone, PartialEq)]
+pub struct Entry {
+    /// uppercased
+    pub word: String,
+    /// index in `List::sources`
+    pub source: usize,
+    /// 1-based, None for builtin lists
+    pub line: Option<usize>,
+    pub info: WordInfo,
+}
+
+/// The union of word lists as read, before words of other lengths are dropped.
+/// In list files, `#` starts a comment and a line is either `WORD WEIGHT [TAG,TAG...]`
+/// or words separated by whitespace, like before weights and tags
+#[derive(Default)]
+pub struct List {
+    /// the files read, `BUILTIN` for the builtin list
+    pub sources: Vec<String>,
+    pub entries: Vec<Entry>,
+    /// lines that can't be read, reported by `check`
+    pub malformed: Vec<Issue>,
+}
+
+impl List {
+    /// Read one list file
+    pub fn read(file: &str) -> Result<List, WordleError> {
+        let mut list = List::default();
+        list.add_file(file)?;
+        Ok(list)
+    }
+    fn add_file(&mut self, file: &str) -> Result<(), WordleError> {
+        let text = utils::str_from_file(file).map_err(|e| WordleError::WordListIo {
+            file: file.to_string(),
+            reason: e.to_string(),
+        })?;
+        let source = self.sources.len();
+        self.sources.push(file.to_string());
+        for (i, line) in text.lines().enumerate() {
+            let fields: Vec<&str> = line.split('#').next().unwrap().split_whitespace().collect();
+            // a number after the first word makes it a weighted entry, so that
+            // `crane slate` still lists two words instead of a word and a tag
+            let weighted = matches!(fields.get(1), Some(f) if f.parse::<f64>().is_ok());
+            if !weighted {
+                self.entries.extend(fields.iter().map(|w| Entry {
+                    word: w.to_ascii_uppercase(),
+                    source,
+                    line: Some(i + 1),
+                    info: WordInfo::default(),
+                }));
+                continue;
+            }
+            let word = fields[0].to_ascii_uppercase();
+            match Self::parse_info(&fields[1..]) {
+                Ok(info) => self.entries.push(Entry {
+                    word,
+                    source,
+                    line: Some(i + 1),
+                    info,
+                }),
+                Err(problem) => self.malformed.push(Issue {
+                    list: file.to_string(),
+                    line: Some(i + 1),
+                    word,
+                    problem,
+                }),
+            }
+        }
+        Ok(())
+    }
+    // the fields after a word: a weight, then optional comma-separated tags
+    fn parse_info(fields: &[&str]) -> Result<WordInfo, Problem> {
+        let mut info = WordInfo::default();
+        let weight: f64 = fields[0].parse().map_err(|_| Problem::BadWeight)?;
+        if !weight.is_finite() || weight <= 0f64 {
+            return Err(Problem::BadWeight);
+        }
+        info.weight = weight;
+        match &fields[1..] {
+            [] => (),
+            [tags] => {
+                info.tags = tags
+                    .split(',')
+                    .filter(|t| !t.is_empty())
+                    .map(|t| t.to_lowercase())
+                    .collect()
+            }
+            _ => return Err(Problem::Malformed),
+        }
+        Ok(info)
+    }
+    fn add_builtin(&mut self, arr: &[&str]) {
+        let source = self.sources.len();
+        self.sources.push(BUILTIN.to_string());
+        self.entries.extend(arr.iter().map(|w| Entry {
+            word: w.to_ascii_uppercase(),
+            source,
+            line: None,
+            info: WordInfo::default(),
+        }));
+    }
+    /// The union of <files>, where `BUILTIN` stands for <builtin>, the builtin list alone
+    /// if there's no file. Words of the --exclude-set files are left out
+    fn union(files: &[String], builtin: &[&str], args: &args::Args) -> Result<List, WordleError> {
+        let mut list = List::default();
+        for file in files {
+            match file.as_str() {
+                BUILTIN => list.add_builtin(builtin),
+                file => list.add_file(file)?,
+            }
+        }
+        if files.is_empty() {
+            list.add_builtin(builtin);
+        }
+        let mut excluded = List::default();
+        for file in args.exclude_set.iter() {
+            excluded.add_file(file)?;
+        }
+        let excluded: HashSet<&str> = excluded.entries.iter().map(|e| e.word.as_str()).collect();
+        list.entries.retain(|e| !excluded.contains(e.word.as_str()));
+        Ok(list)
+    }
+    /// --final-set, or the builtin final list
+    pub fn final_set(args: &args::Args) -> Result<List, WordleError> {
+        List::union(&args.final_set, builtin_words::FINAL, args)
+    }
+    /// --acceptable-set, or the builtin acceptable list
+    pub fn acceptable_set(args: &args::Args) -> Result<List, WordleError> {
+        List::union(&args.acceptable_set, builtin_words::ACCEPTABLE, args)
+    }
+    fn source(&self, entry: &Entry) -> &str {
+        &self.sources[entry.source]
+    }
+    // words of <len> characters, each once with what the first source tells, in the order of the list
+    fn words(&self, len: usize) -> Vec<(String, WordInfo)> {
+        let mut seen = HashSet::new();
+        self.entries
+            .iter()
+            .filter(|e| e.word.len() == len && seen.insert(e.word.as_str()))
+            .map(|e| (e.word.clone(), e.info.clone()))
+            .collect()
+    }
+    /// Every playable word once, sorted, whatever its length, as lines of a list file
+    pub fn normalized(&self) -> Vec<String> {
+        let mut seen = HashSet::new();
+        let mut entries: Vec<&Entry> = self
+            .entries
+            .iter()
+            .filter(|e| alphabetic(&e.word) && seen.insert(e.word.as_str()))
+            .collect();
+        entries.sort_by(|e1, e2| e1.word.cmp(&e2.word));
+        entries
+            .into_iter()
+            .map(|e| {
+                let mut line = e.word.clone();
+                // tags only follow a weight
+                if e.info.weight != 1f64 || !e.info.tags.is_empty() {
+                    line += &format!(" {}", e.info.weight);
+                }
+                if !e.info.tags.is_empty() {
+                    line += &format!(" {}", e.info.tags.join(","));
+                }
+                line
+            })
+            .collect()
+    }
+}
+
+fn alphabetic(word: &str) -> bool {
+    word.bytes().all(|b| b.is_ascii_uppercase())
+}
+
+/// What's wrong with an entry of a word list
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Problem {
+    /// not only made of letters, it can't be played
+    NotAlphabetic,
+    /// not of the length played, ignored
+    WrongLength { len: usize },
+    /// already on line <first>, ignored, since it would be drawn more often
+    Duplicate { first: Option<usize> },
+    /// a final word that can't be guessed
+    NotAcceptable,
+    /// a weight that isn't a positive number
+    BadWeight,
+    /// more than tags after the weight of a word
+    Malformed,
+}
+
+/// A problem found by `check`, with where it is
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Issue {
+    pub list: String,
+    pub line: Option<usize>,
+    pub word: String,
+    pub problem: Problem,
+}
+
+impl Issue {
+    /// Errors stop the game, the other problems are only warned about
+    pub fn is_error(&self) -> bool {
+        !matches!(
+            self.problem,
+            Problem::WrongLength { .. } | Problem::Duplicate { .. }
+        )
+    }
+    /// Notes are only shown by `wordle words check`, words of other lengths are fine
+    pub fn is_note(&self) -> bool {
+        matches!(self.problem, Problem::WrongLength { .. })
+    }
+    pub fn to_error(&self) -> WordleError {
+        match self.problem {
+            Problem::NotAcceptable => WordleError::WordNotAcceptable {
+                word: self.word.clone(),
+                line: self.line,
+            },
+            Problem::BadWeight | Problem::Malformed => WordleError::MalformedList {
+                file: self.list.clone(),
+                line: self.line.unwrap_or(0),
+            },
+            _ => WordleError::InvalidWord {
+                file: self.list.clone(),
+                word: self.word.clone(),
+                line: self.line,
+            },
+        }
+    }
+}
+
+impl fmt::Display for Issue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.line {
+            Some(line) => write!(f, "{}:{}: {} ", self.list, line, self.word)?,
+            None => write!(f, "{}: {} ", self.list, self.word)?,
+        }
+        match self.problem {
+            Problem::NotAlphabetic => write!(f, "isn't made of letters only"),
+            Problem::WrongLength { len } => write!(f, "doesn't have {} characters", len),
+            Problem::Duplicate { first: Some(line) } => write!(f, "is already on line {}", line),
+            Problem::Duplicate { first: None } => write!(f, "is listed twice"),
+            Problem::NotAcceptable => write!(f, "isn't in the acceptable set"),
+            Problem::BadWeight => write!(f, "has a weight that isn't a positive number"),
+            Problem::Malformed => write!(f, "has more than tags after its weight"),
+        }
+    }
+}
+
+/// Every problem of <final_set> and <acceptable_set> when playing words of <len> characters,
+/// in the order of the lists. A word in several sources isn't a duplicate, the first one counts
+pub fn check(final_set: &List, acceptable_set: &List, len: usize) -> Vec<Issue> {
+    let acceptable: HashSet<&str> = acceptable_set
+        .entries
+        .iter()
+        .map(|e| e.word.as_str())
+        .collect();
+    let mut issues = vec![];
+    for (list, is_final) in [(final_set, true), (acceptable_set, false)] {
+        issues.extend(list.malformed.iter().cloned());
+        let mut first = HashMap::<&str, &Entry>::new();
+        for entry in list.entries.iter() {
+            let word = entry.word.as_str();
+            let problem = if !alphabetic(word) {
+                Problem::NotAlphabetic
+            } else if let Some(first) = first.get(word) {
+                if first.source != entry.source {
+                    continue;
+                }
+                Problem::Duplicate { first: first.line }
+            } else if word.len() != len {
+                // one list can serve several lengths, only the words played need a check
+                Problem::WrongLength { len }
+            } else if is_final && !acceptable.contains(word) {
+                Problem::NotAcceptable
+            } else {
+                first.insert(word, entry);
+                continue;
+            };
+            first.entry(word).or_insert(entry);
+            issues.push(Issue {
+                list: list.source(entry).to_string(),
+                line: entry.line,
+                word: entry.word.clone(),
+                problem,
+            });
+        }
+    }
+    issues
 }
 
 impl Words {
-    pub fn new(args: &args::Args) -> Words {
-        let mut final_words_list: Vec<String> = match args.final_set.as_ref() {
-            None => utils::from_arr(builtin_words::FINAL),
-            Some(f) => utils::arr_from_file(f),
-        };
-        final_words_list
-            .iter_mut()
-            .for_each(|x| x.make_ascii_uppercase());
-        let final_words: HashSet<String> = final_words_list.iter().map(|x| x.clone()).collect();
-        let mut valid_words_list: Vec<String> = match args.acceptable_set.as_ref() {
-            None => utils::from_arr(builtin_words::ACCEPTABLE),
-            Some(f) => utils::arr_from_file(f),
-        };
-        valid_words_list
-            .iter_mut()
-            .for_each(|x| x.make_ascii_uppercase());
-        let valid_words: HashSet<String> = valid_words_list.into_iter().collect();
-        for word in final_words_list.iter() {
-            assert!(valid_words.contains(word));
+    pub fn new(args: &args::Args) -> Result<Words, WordleError> {
+        let len = args.length.unwrap();
+        let (final_set, acceptable_set) = (List::final_set(args)?, List::acceptable_set(args)?);
+        let issues = check(&final_set, &acceptable_set, len);
+        if let Some(issue) = issues.iter().find(|i| i.is_error()) {
+            return Err(issue.to_error());
+        }
+        // words of other lengths are dropped, so that one list can serve several lengths
+        let mut final_words_list = final_set.words(len);
+        if final_words_list.is_empty() {
+            return Err(WordleError::EmptyWordList { len });
+        }
+        if let Some(category) = args.category.as_ref() {
+            let category = category.to_lowercase();
+            final_words_list.retain(|(_, i)| i.tags.contains(&category));
+            if final_words_list.is_empty() {
+                return Err(WordleError::InvalidArg(format!(
+                    "no word of the final set is tagged {}",
+                    category
+                )));
+            }
+        }
+        // what the final set tells about a word comes first
+        let mut info: HashMap<String, WordInfo> = acceptable_set.words(len).into_iter().collect();
+        let valid_words: HashSet<String> = info.keys().cloned().collect();
+        info.extend(final_words_list.iter().cloned());
+        let mut final_words_list: Vec<String> =
+            final_words_list.into_iter().map(|(w, _)| w).collect();
+        let final_words: HashSet<String> = final_words_list.iter().cloned().collect();
+        let boards = args.boards.unwrap_or(1);
+        if args.random && boards > final_words.len() {
+            return Err(WordleError::InvalidArg(format!(
+                "{} boards need as many different answers, the final set has {}",
+                boards,
+                final_words.len()
+            )));
         }
         if args.random {
             let mut rng = rand::rngs::StdRng::seed_from_u64(args.seed.unwrap());
-            final_words_list.shuffle(&mut rng);
+            match args.weighting.unwrap_or_default() {
+                Weighting::Uniform => final_words_list.shuffle(&mut rng),
+                Weighting::Frequency => {
+                    // weighted shuffle: sorting by u^(1/weight) for a uniform u in [0, 1)
+                    // puts a word first with a chance proportional to its weight.
+                    // Weights are positive, bad ones were reported by `check`
+                    let mut keyed: Vec<(f64, String)> = final_words_list
+                        .into_iter()
+                        .map(|w| (rng.gen::<f64>().powf(1f64 / info[&w].weight), w))
+                        .collect();
+                    keyed.sort_by(|(k1, _), (k2, _)| k2.total_cmp(k1));
+                    final_words_list = keyed.into_iter().map(|(_, w)| w).collect();
+                }
+            }
         }
-        Words {
+        Ok(Words {
             final_list: final_words_list,
             r#final: final_words,
             valid: valid_words,
+            info,
+            warnings: issues.into_iter().filter(|i| !i.is_note()).collect(),
+        })
+    }
+    /// Weight given to <word> by the lists, 1 by default
+    pub fn weight(&self, word: &str) -> f64 {
+        self.info.get(word).map_or(1f64, |i| i.weight)
+    }
+    /// Tags given to <word> by the lists
+    pub fn tags(&self, word: &str) -> &[String] {
+        self.info.get(word).map_or(&[], |i| i.tags.as_slice())
+    }
+    /// Identify the word lists played with, whatever their order:
+    /// a FNV-1a hash of the sorted final and acceptable lists
+    pub fn fingerprint(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        for set in [&self.r#final, &self.valid] {
+            let mut list: Vec<&String> = set.iter().collect();
+            list.sort();
+            for b in list
+                .iter()
+                .flat_map(|w| w.bytes().chain([b'\n']))
+                .chain([0])
+            {
+                hash ^= b as u64;
+                hash = hash.wrapping_mul(0x100000001b3);
+            }
         }
+        format!("{:016x}", hash)
     }
 }
@@ -1,3 +1,6 @@
+// shared by several test crates, each using only part of it
+#![allow(dead_code)]
+
 use std::env::consts::EXE_EXTENSION;
 use std::fs::File;
 use std::io::prelude::*;
@@ -9,7 +12,6 @@ use std::sync::Once;
 use assert_json_diff::assert_json_eq;
 use lazy_static::lazy_static;
 use pretty_assertions::assert_eq;
-use serde_json;
 
 // The code was originally written by Jack O'Connor (@oconnor663)
 // Taken from https://github.com/oconnor663/os_pipe.rs/blob/f41c58e503e1efc5e4d0edfcd2e756b3a81b4232/src/lib.rs#L281-L314
@@ -41,7 +43,7 @@ fn build_and_find_path(name: &str) -> PathBuf {
     static CARGO_BUILD_ONCE: Once = Once::new();
     CARGO_BUILD_ONCE.call_once(|| {
         let mut build_command = Command::new("cargo");
-        build_command.args(&["build", "--quiet"]);
+        build_command.args(["build", "--quiet"]);
         if !cfg!(debug_assertions) {
             build_command.arg("--release");
         }
@@ -161,8 +163,9 @@ impl TestCase {
         self.run_and_compare_result();
 
         // load state and compare with answer
-        let run_state: serde_json::Value =
+        let mut run_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&run_state_file).unwrap())).unwrap();
+        strip_clock(&mut run_state);
         let answer_state: serde_json::Value =
             serde_json::from_reader(BufReader::new(File::open(&after_state_file).unwrap()))
                 .unwrap();
@@ -181,4 +184,44 @@ impl TestCase {
             self.name
         );
     }
+
+    pub fn run_and_expect_exit_code(&self, code: i32) {
+        let command = self.execute_program_and_feed_input();
+        let status = command
+            .wait_with_output()
+            .expect("failed to wait on process")
+            .status;
+        assert_eq!(
+            status.code(),
+            Some(code),
+            "case {} should exit with code {}",
+            self.name,
+            code
+        );
+    }
+}
+
+// timestamps of games depend on when the test runs: check they're consistent,
+// then drop them so that the rest of the state can be compared
+fn strip_clock(state: &mut serde_json::Value) {
+    let mut games: Vec<&mut serde_json::Value> = vec![];
+    let state = state.as_object_mut().unwrap();
+    for (key, value) in state.iter_mut() {
+        match (key.as_str(), value) {
+            ("games", serde_json::Value::Array(g)) => games.extend(g.iter_mut()),
+            ("unfinished", g) => games.push(g),
+            _ => (),
+        }
+    }
+    for meta in games.into_iter().filter_map(|g| g.get_mut("meta")) {
+        let meta = meta.as_object_mut().unwrap();
+        let start = meta.remove("start").unwrap().as_i64().unwrap();
+        let end = meta.remove("end").and_then(|v| v.as_i64());
+        let duration = meta.remove("duration").and_then(|v| v.as_i64());
+        // unfinished games have neither
+        match (end, duration) {
+            (Some(end), Some(duration)) => assert_eq!(end - start, duration),
+            (end, duration) => assert!(end.is_none() && duration.is_none()),
+        }
+    }
 }
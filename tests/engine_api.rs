@@ -0,0 +1,432 @@
+use wordle::{
+    args::Args,
+    daily,
+    game::{self, Boards, Difficulty, Game, LoadError, Stats, Unfinished},
+    pattern::{score, Pattern},
+    share::{self, Shared},
+    solver,
+    words::{self, List, Problem, Words},
+};
+
+#[test]
+fn test_score_duplicated_letters() {
+    assert_eq!(score("HELLO", "LEVEL").to_string(), "YGRRY");
+    assert_eq!(score("CRANE", "CRANE").to_string(), "GGGGG");
+    assert_eq!(score("ABBEY", "BABES").to_string(), "YYGGR");
+    assert!(score("CRANE", "CRANE").solved());
+}
+
+#[test]
+fn test_pattern_from_str() {
+    let pattern: Pattern = "gyyxg".parse().unwrap();
+    assert_eq!(pattern.to_string(), "GYYRG");
+    assert_eq!(pattern, score("CRANE", "CARTE"));
+    assert!("GYB".parse::<Pattern>().is_err());
+}
+
+#[test]
+fn test_candidates() {
+    let mut args = Args::default();
+    args.refine().unwrap();
+    let words = Words::new(&args).unwrap();
+    let mut game = Game::new(5, 6);
+    game.set_answer("CRANE".to_string());
+    assert_eq!(game.candidates(&words).len(), words.final_list.len());
+    game.guess("SLATE".to_string());
+    game.guess("BRINE".to_string());
+    let candidates = game.candidates(&words);
+    assert!(candidates.contains(&"CRANE"));
+    for w in candidates {
+        assert_eq!(score(w, "SLATE"), score("CRANE", "SLATE"));
+        assert_eq!(score(w, "BRINE"), score("CRANE", "BRINE"));
+    }
+}
+
+#[test]
+fn test_rank_ties() {
+    // equal rates come out in the same order on every run, alphabetically
+    let mut args = Args {
+        final_set: vec!["tests/data/11_solver_final.txt".to_string()],
+        acceptable_set: vec!["tests/data/11_solver_acceptable.txt".to_string()],
+        ..Default::default()
+    };
+    args.refine().unwrap();
+    let words = Words::new(&args).unwrap();
+    let game = Game::new(5, 6);
+    let ranked = solver::rank(&game, &words, Difficulty::Normal, &solver::MaxEntropy);
+    let pos = |w: &str| ranked.iter().position(|(x, _)| *x == w).unwrap();
+    let (blame, grace) = (pos("BLAME"), pos("GRACE"));
+    assert_eq!(ranked[blame].1, ranked[grace].1);
+    assert!(blame < grace);
+    assert_eq!(
+        solver::entropy("BLAME", &game.candidates(&words)),
+        ranked[blame].1
+    );
+}
+
+#[test]
+fn test_rate_no_candidate() {
+    // a game whose feedback rules out every answer still rates guesses
+    for kind in solver::StrategyKind::ALL {
+        assert!(kind.strategy().rate("CRANE", &[]).is_finite());
+    }
+}
+
+#[test]
+fn test_strict_check() {
+    let mut game = Game::new(5, 6);
+    game.set_answer("CRANE".to_string());
+    game.guess("ARAME".to_string());
+    // second A is red, so only one A is allowed
+    assert!(game.hard_check("BRAAE").is_ok());
+    assert_eq!(
+        game.strict_check("BRAAE").unwrap_err().to_string(),
+        "guess must contain at most 1 A"
+    );
+    assert_eq!(
+        game.strict_check("BRAME").unwrap_err().to_string(),
+        "guess must not contain M"
+    );
+    assert_eq!(
+        game.hard_check("BLAME").unwrap_err().to_string(),
+        "position 2 must be R"
+    );
+    assert!(game.strict_check("BRAKE").is_ok());
+}
+
+#[test]
+fn test_adversarial() {
+    let pool = ["CRANE", "CRATE", "GRACE", "BLOAT"];
+    let mut game = Game::new(5, 6);
+    game.set_adversarial(pool.iter().map(|w| w.to_string()).collect());
+    // CRANE and CRATE are scored GGGRG, the largest group
+    assert!(!game.guess("CRAFE".to_string()));
+    assert_eq!(game.show_patterns()[0].to_string(), "GGGRG");
+    assert_eq!(game.show_answer(), "");
+    assert!(!game.guess("CRANE".to_string()));
+    assert_eq!(game.show_answer(), "CRATE");
+    assert!(game.guess("CRATE".to_string()));
+}
+
+#[test]
+fn test_boards() {
+    let answers = ["CRANE", "SLATE"].iter().map(|w| w.to_string()).collect();
+    let mut boards = Boards::new(5, 7, answers);
+    assert!(!boards.guess("CRANE".to_string()));
+    assert!(boards.boards()[0].won());
+    // the solved board takes no more guesses
+    assert!(boards.guess("SLATE".to_string()));
+    assert_eq!(boards.boards()[0].show_guesses().len(), 1);
+    assert_eq!(boards.rounds(), 2);
+    assert!(boards.ended());
+}
+
+#[test]
+fn test_daily() {
+    let date = daily::parse_date("2022-01-31").unwrap();
+    assert_eq!(daily::format_date(date), "2022-01-31");
+    assert_eq!(daily::puzzle_number(daily::epoch()), Some(0));
+    assert_eq!(daily::puzzle_number(date), Some(226));
+    assert_eq!(daily::date_of(226), Some(date));
+    assert!(daily::date_of(3_000_000).is_none());
+    assert!(daily::date_of(usize::MAX).is_none());
+    assert!(daily::puzzle_number(daily::parse_date("2021-06-18").unwrap()).is_none());
+    assert!(daily::parse_date("2022-02-30").is_err());
+}
+
+#[test]
+fn test_share() {
+    let mut game = Game::new(5, 2);
+    game.set_answer("HELLO".to_string());
+    game.guess("LEVEL".to_string());
+    assert_eq!(game.share(false, false), "Wordle -/2\n🟨🟩⬛⬛🟨");
+    game.guess("WORLD".to_string());
+    assert_eq!(
+        game.share(false, false),
+        "Wordle X/2\n🟨🟩⬛⬛🟨\n⬛🟨⬛🟩⬛"
+    );
+    assert_eq!(
+        game.share(true, true),
+        "Wordle X/2*\n🟦🟧⬛⬛🟦\n⬛🟦⬛🟧⬛"
+    );
+}
+
+#[test]
+fn test_import_share() {
+    let text = "Wordle 1,234 2/6*\n\n⬛🟨⬛⬛⬛\n🟩🟩🟩🟩🟩\nWordle 1235 X/3\n⬛⬛⬛⬛⬛\n⬛⬛⬛⬛⬛\n🟨⬛⬛⬛⬛\n";
+    let shared: Vec<Shared> = share::parse(text).into_iter().map(|s| s.unwrap()).collect();
+    assert_eq!(shared.len(), 2);
+    assert_eq!(
+        (shared[0].puzzle, shared[0].score, shared[0].hard),
+        (1234, Some(2), true)
+    );
+    assert_eq!(
+        (shared[1].puzzle, shared[1].score, shared[1].rounds),
+        (1235, None, 3)
+    );
+    assert_eq!(shared[0].patterns[0].to_string(), "RYRRR");
+    let mut stats = Stats::new();
+    assert!(stats.store_shared(&shared[0]));
+    assert!(!stats.store_shared(&shared[0]));
+    assert!(stats.store_shared(&shared[1]));
+    // counted in results, but there's no word to count
+    assert_eq!(stats.feed_stats(6, 1), (1, 0, 2.0));
+    assert_eq!(stats.feed_stats(3, 1), (0, 1, 0.0));
+    assert!(stats.feed_words().is_empty());
+    // the grid must end with the winning guess
+    assert!(share::parse("Wordle 1 1/6\n⬛⬛⬛⬛⬛\n")[0].is_err());
+    assert_eq!(
+        share::parse("Wordle 1 3/6\n⬛⬛⬛⬛⬛\n🟩🟩🟩🟩🟩\n")[0],
+        Err("line 1: the grid has 2 rows for a score of 3".to_string())
+    );
+    assert_eq!(
+        share::parse("Wordle 1 X/6\n⬛⬛⬛⬛⬛\n")[0],
+        Err("line 1: the grid has 1 rows for X out of 6".to_string())
+    );
+    // a puzzle past the last date there is
+    assert_eq!(
+        share::parse("Wordle 3000000 1/6\n🟩🟩🟩🟩🟩\n")[0],
+        Err("line 1: can't read the result in \"3000000 1/6\"".to_string())
+    ); // an older result imported after a win doesn't break the streak
+    let mut stats = Stats::load(
+        r#"{"version": 3, "games": [{"answer": "CRANE", "guesses": ["CRANE"], "rounds": 6,
+            "meta": {"start": 1710892800, "mode": "random", "words": "0"}}]}"#,
+    )
+    .unwrap();
+    let lost = share::parse(&format!("Wordle 1000 X/6\n{}", "⬛⬛⬛⬛⬛\n".repeat(6)));
+    assert!(stats.store_shared(lost[0].as_ref().unwrap()));
+    let summary = stats.feed_summary(6, 1);
+    assert_eq!(
+        (summary.failed, summary.current_streak, summary.max_streak),
+        (1, 1, 1)
+    );
+}
+
+#[test]
+fn test_state_version() {
+    // files from before versioning are migrated
+    let stats =
+        Stats::load(r#"{"total_rounds": 1, "games": [{"answer": "CRANE", "guesses": ["CRANE"]}]}"#)
+            .unwrap();
+    assert_eq!(stats.feed_stats(6, 1), (1, 0, 1.0));
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["version"], game::VERSION);
+    assert_eq!(
+        Stats::load(r#"{"version": 99, "games": []}"#).err(),
+        Some(LoadError::Newer(99))
+    );
+    // version 2 kept the hard mode next to meta, and the default budget out
+    let stats = Stats::load(
+        r#"{"version": 2, "games": [{"answer": "CRANE", "guesses": ["CRANE"], "hard": true,
+            "meta": {"start": 0, "end": 1, "duration": 1, "mode": "random", "words": "0"}}]}"#,
+    )
+    .unwrap();
+    let json = serde_json::to_value(&stats).unwrap();
+    assert_eq!(json["games"][0]["meta"]["difficulty"], "hard");
+    assert_eq!(json["games"][0]["meta"].get("hard"), None);
+    assert_eq!(json["games"][0].get("hard"), None);
+    assert_eq!(json["games"][0]["rounds"], 6);
+    // version 3 only knew whether the game was hard, version 4 keeps the level
+    let stats = Stats::load(
+        r#"{"version": 3, "games": [], "unfinished": {"answer": "CRANE", "guesses": [],
+            "meta": {"start": 0, "mode": "random", "hard": true, "words": "0"}}}"#,
+    )
+    .unwrap();
+    let mut game = match stats.unfinished() {
+        Some(Unfinished::Game(game)) => *game,
+        _ => panic!("no unfinished game"),
+    };
+    assert_eq!(game.difficulty(), Difficulty::Hard);
+    let meta = game.meta().unwrap().clone();
+    game.set_meta(meta, Difficulty::Strict);
+    let mut stats = Stats::new();
+    stats.set_unfinished(&game);
+    let stats = Stats::load(&serde_json::to_string(&stats).unwrap()).unwrap();
+    match stats.unfinished() {
+        Some(Unfinished::Game(game)) => assert_eq!(game.difficulty(), Difficulty::Strict),
+        _ => panic!("no unfinished game"),
+    }
+}
+
+#[test]
+fn test_answers() {
+    // answers of every board count, imported results have none
+    let stats = Stats::load(
+        r#"{"version": 2, "games": [
+            {"answer": "CRANE", "guesses": ["CRANE"]},
+            {"answer": "", "answers": ["SLATE", "TRACE"], "guesses": []},
+            {"answer": "", "guesses": [], "patterns": ["GGGGG"], "puzzle": 3}
+        ]}"#,
+    )
+    .unwrap();
+    let mut answers: Vec<String> = stats.answers().into_iter().collect();
+    answers.sort();
+    assert_eq!(answers, ["CRANE", "SLATE", "TRACE"]);
+}
+
+#[test]
+fn test_unfinished() {
+    // guesses are replayed, so the constraints of hard modes come back
+    let mut game = Game::new(5, 6);
+    game.set_answer("CRANE".to_string());
+    game.guess("SLATE".to_string());
+    let mut stats = Stats::new();
+    stats.set_unfinished(&game);
+    let json = serde_json::to_string(&stats).unwrap();
+    let resumed = match Stats::load(&json).unwrap().unfinished() {
+        Some(Unfinished::Game(game)) => game,
+        _ => panic!("the game should be resumed"),
+    };
+    assert_eq!(resumed.show_patterns(), game.show_patterns());
+    assert_eq!(resumed.show_col(), game.show_col());
+    assert!(resumed.check("BRINK", Difficulty::Hard).is_err());
+    stats.clear_unfinished();
+    assert!(stats.unfinished().is_none());
+    // every board keeps its own colors
+    let mut boards = Boards::new(5, 7, vec!["CRANE".to_string(), "HELLO".to_string()]);
+    boards.guess("CRANE".to_string());
+    stats.set_unfinished_boards(&boards);
+    match stats.unfinished() {
+        Some(Unfinished::Boards(resumed)) => {
+            assert!(resumed.boards()[0].won() && !resumed.boards()[1].won());
+            assert_eq!(resumed.rounds(), 1);
+        }
+        _ => panic!("the boards should be resumed"),
+    }
+}
+
+#[test]
+fn test_words_check() {
+    let final_set = List::read("tests/data/25_invalid_final.txt").unwrap();
+    let mut args = Args::default();
+    args.refine().unwrap();
+    let acceptable_set = List::acceptable_set(&args).unwrap();
+    let issues = words::check(&final_set, &acceptable_set, 5);
+    let problems: Vec<(Option<usize>, Problem)> =
+        issues.iter().map(|i| (i.line, i.problem)).collect();
+    assert_eq!(
+        problems,
+        [
+            (Some(3), Problem::NotAlphabetic),
+            (Some(4), Problem::Duplicate { first: Some(1) })
+        ]
+    );
+    assert!(issues[0].is_error() && !issues[1].is_error());
+    assert_eq!(issues[0].to_error().exit_code(), 12);
+    assert_eq!(final_set.normalized(), ["CRANE", "SLATE"]);
+    // final words of other lengths needn't be acceptable, and aren't warned about
+    let mut args = Args {
+        final_set: vec!["tests/data/25_mixed_final.txt".to_string()],
+        acceptable_set: vec!["tests/data/25_mixed_acceptable.txt".to_string()],
+        length: Some(4),
+        ..Default::default()
+    };
+    args.refine().unwrap();
+    let words = Words::new(&args).unwrap();
+    assert_eq!(words.final_list, ["LOVE"]);
+    assert!(words.warnings.is_empty());
+    let issues = words::check(
+        &List::final_set(&args).unwrap(),
+        &List::acceptable_set(&args).unwrap(),
+        4,
+    );
+    assert_eq!(issues[0].problem, Problem::WrongLength { len: 4 });
+    assert!(issues[0].is_note() && !issues[0].is_error());
+}
+
+#[test]
+fn test_list_format() {
+    let list = List::read("tests/data/26_tagged_list.txt").unwrap();
+    let words: Vec<(&str, Option<usize>)> = list
+        .entries
+        .iter()
+        .map(|e| (e.word.as_str(), e.line))
+        .collect();
+    assert_eq!(
+        words,
+        [("YEETS", Some(2)), ("RIZZY", Some(3)), ("VIBEY", Some(4))]
+    );
+    assert_eq!(list.entries[0].info.weight, 0.5);
+    assert_eq!(list.entries[0].info.tags, ["slang", "verb"]);
+    assert_eq!(list.entries[2].info.weight, 1.0);
+    // without a weight, every word of a line is a word of the list
+    let list = List::read("tests/data/26_multi_word_list.txt").unwrap();
+    let words: Vec<(&str, Option<usize>)> = list
+        .entries
+        .iter()
+        .map(|e| (e.word.as_str(), e.line))
+        .collect();
+    assert_eq!(
+        words,
+        [
+            ("CRANE", Some(2)),
+            ("SLATE", Some(2)),
+            ("TRACE", Some(3)),
+            ("BRAKE", Some(3)),
+            ("GRACE", Some(3))
+        ]
+    );
+    let mut args = Args::default();
+    args.refine().unwrap();
+    let issues = words::check(&list, &List::acceptable_set(&args).unwrap(), 5);
+    assert_eq!(issues.len(), 1);
+    assert_eq!(
+        (issues[0].line, issues[0].problem),
+        (Some(4), Problem::Malformed)
+    );
+    // sources are united, excluded words are left out of both sets
+    let mut args = Args::default();
+    args.final_set = vec![
+        words::BUILTIN.to_string(),
+        "tests/data/26_tagged_list.txt".to_string(),
+    ];
+    args.acceptable_set = args.final_set.clone();
+    args.exclude_set = vec!["tests/data/26_excluded.txt".to_string()];
+    args.refine().unwrap();
+    let words = Words::new(&args).unwrap();
+    assert!(words.r#final.contains("RIZZY") && words.r#final.contains("SLATE"));
+    assert!(!words.r#final.contains("CRANE") && !words.valid.contains("CRANE"));
+    assert_eq!(words.weight("RIZZY"), 2.0);
+    assert_eq!(words.weight("SLATE"), 1.0);
+    assert_eq!(words.tags("VIBEY"), ["slang"]);
+}
+
+#[test]
+fn test_weighting() {
+    let mut args = Args::default();
+    args.random = true;
+    args.seed = Some(7);
+    args.final_set = vec![
+        words::BUILTIN.to_string(),
+        "tests/data/26_tagged_list.txt".to_string(),
+    ];
+    args.acceptable_set = args.final_set.clone();
+    args.category = Some("Slang".to_string());
+    args.refine().unwrap();
+    let uniform = Words::new(&args).unwrap();
+    let mut sorted = uniform.final_list.clone();
+    sorted.sort();
+    assert_eq!(sorted, ["RIZZY", "VIBEY", "YEETS"]);
+    // the same seed gives the same answers
+    assert_eq!(Words::new(&args).unwrap().final_list, uniform.final_list);
+    // every word comes up once, heavier words first more often
+    args.category = None;
+    args.final_set = vec!["tests/data/26_tagged_list.txt".to_string()];
+    args.weighting = Some(words::Weighting::Frequency);
+    let (mut rizzy, mut yeets) = (0, 0);
+    for seed in 0..50 {
+        args.seed = Some(seed);
+        let words = Words::new(&args).unwrap();
+        let mut sorted = words.final_list.clone();
+        sorted.sort();
+        assert_eq!(sorted, ["RIZZY", "VIBEY", "YEETS"]);
+        rizzy += (words.final_list[0] == "RIZZY") as usize;
+        yeets += (words.final_list[0] == "YEETS") as usize;
+    }
+    assert!(rizzy > 2 * yeets);
+    args.category = Some("verb".to_string());
+    args.seed = Some(7);
+    assert_eq!(Words::new(&args).unwrap().final_list, ["YEETS"]);
+    args.category = Some("food".to_string());
+    assert_eq!(Words::new(&args).err().unwrap().exit_code(), 4);
+}
@@ -0,0 +1,204 @@
+use ntest::timeout;
+
+mod common;
+use common::TestCase;
+
+#[test]
+#[timeout(2000)]
+fn test_09_word_length() {
+    // 4-character words, words of other lengths in the lists are ignored
+    TestCase::read("09_01_word_length").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_10_guess_budget() {
+    // stats only count games played with the same budget
+    TestCase::read("10_01_rounds").run_and_compare_game_state();
+    // 0 for unlimited guesses
+    TestCase::read("10_02_unlimited_rounds").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_11_solver() {
+    // rank the best openers
+    TestCase::read("11_01_solver_rank").run_and_compare_result();
+    // let the solver play in hard mode
+    TestCase::read("11_02_solver_play").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_12_solver_strategies() {
+    // every strategy against every word in the final set
+    TestCase::read("12_01_bench").run_and_compare_result();
+    // rank openers by another strategy
+    TestCase::read("12_02_strategy").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_13_assist() {
+    // colors typed by the user, with and without the guess
+    TestCase::read("13_01_assist").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_14_strict_mode() {
+    // grey letters and yellow letters at the same position are rejected
+    TestCase::read("14_01_strict_mode").run_and_compare_result();
+    // explain why guesses are invalid
+    TestCase::read("14_02_strict_mode_verbose").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_15_adversarial_mode() {
+    // the answer dodges guesses until a single word is left
+    TestCase::read("15_01_adversarial").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_16_multi_boards() {
+    // solved boards stop printing, stats are kept per board count
+    TestCase::read("16_01_multi_boards").run_and_compare_result();
+    // random answers of every board are shown on failure
+    TestCase::read("16_02_multi_boards_failed").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_17_daily() {
+    // the answer comes from the date, which is recorded in stats
+    TestCase::read("17_01_daily").run_and_compare_game_state();
+    // a day already played isn't counted twice
+    TestCase::read("17_02_daily_replayed").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_18_archive() {
+    // list past puzzles, play the picked ones, and list them again with results
+    TestCase::read("18_01_archive").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_19_share() {
+    // the puzzle number is shown for daily puzzles, * for hard mode
+    TestCase::read("19_01_share").run_and_compare_result();
+    // boards side by side, solved ones left white
+    TestCase::read("19_02_share_high_contrast").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_20_import_share() {
+    // results in a chat log are counted once, malformed ones are reported
+    TestCase::read("20_01_import_share").run_and_compare_game_state();
+    // without --state the results would be lost
+    TestCase::read("20_02_import_share_no_state").run_and_expect_exit_code(3);
+}
+
+#[test]
+#[timeout(2000)]
+fn test_21_histogram() {
+    // guess distribution, current and max streak, win rate
+    TestCase::read("21_01_histogram").run_and_compare_game_state();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_22_recover_state() {
+    // a corrupt state file is moved aside and a fresh one saved after the game
+    TestCase::read("22_01_recover_state").run_and_compare_game_state();
+    let backups: Vec<_> = std::fs::read_dir("tests/cases")
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .filter(|p| {
+            let name = p.file_name().unwrap().to_str().unwrap();
+            name.starts_with("22_01_recover_state.run.json.corrupt-")
+        })
+        .collect();
+    assert!(!backups.is_empty());
+    for backup in backups {
+        std::fs::remove_file(backup).unwrap();
+    }
+    // a file from a later build isn't corrupt, it's left where it is
+    let newer = std::fs::read_to_string("tests/data/22_newer_state.json").unwrap();
+    TestCase::read("22_02_newer_state").run_and_expect_exit_code(14);
+    assert_eq!(
+        std::fs::read_to_string("tests/data/22_newer_state.json").unwrap(),
+        newer
+    );
+}
+
+#[test]
+#[timeout(2000)]
+fn test_23_resume() {
+    // the game left unfinished is resumed in the hard mode it was started with
+    TestCase::read("23_01_resume_game").run_and_compare_game_state();
+    // the game in progress is saved when the input ends
+    TestCase::read("23_02_quit_game").run_and_compare_game_state();
+    // a game asked for by -w is played instead of the unfinished one
+    TestCase::read("23_03_resume_other_word").run_and_compare_game_state();
+    // a guess isn't taken as the answer to resume
+    TestCase::read("23_04_resume_unexpected").run_and_expect_exit_code(15);
+}
+
+#[test]
+#[timeout(2000)]
+fn test_24_exit_codes() {
+    // each kind of error has its own exit code
+    TestCase::read("05_03_conflict_args").run_and_expect_exit_code(3);
+    TestCase::read("24_05_adversarial_seed").run_and_expect_exit_code(3);
+    TestCase::read("24_08_daily_seed").run_and_expect_exit_code(3);
+    TestCase::read("24_09_weighting_not_random").run_and_expect_exit_code(3);
+    TestCase::read("24_10_no_repeat_no_state").run_and_expect_exit_code(3);
+    TestCase::read("24_03_date_before_epoch").run_and_expect_exit_code(4);
+    TestCase::read("24_12_date_in_future").run_and_expect_exit_code(4);
+    TestCase::read("24_04_invalid_length").run_and_expect_exit_code(4);
+    TestCase::read("24_06_day_zero").run_and_expect_exit_code(4);
+    TestCase::read("24_07_boards_over_list").run_and_expect_exit_code(4);
+    TestCase::read("24_11_boards_overflow").run_and_expect_exit_code(4);
+    TestCase::read("24_01_invalid_config").run_and_expect_exit_code(5);
+    TestCase::read("24_02_missing_word_list").run_and_expect_exit_code(6);
+    TestCase::read("06_02_invalid_word_list").run_and_expect_exit_code(7);
+    TestCase::read("07_03_invalid_json_format").run_and_expect_exit_code(9);
+    TestCase::read("24_13_unexpected_answer").run_and_expect_exit_code(15);
+}
+
+#[test]
+#[timeout(2000)]
+fn test_25_words_check() {
+    // duplicates and words of other lengths are warned about
+    TestCase::read("25_01_words_check").run_and_compare_result();
+    // words that can't be played fail the check
+    TestCase::read("25_02_words_check_invalid").run_and_expect_exit_code(12);
+}
+
+#[test]
+#[timeout(2000)]
+fn test_26_list_union() {
+    // the builtin lists plus a list file with weights, tags and comments, minus excluded words
+    TestCase::read("26_01_list_union").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_27_weighting() {
+    // answers restricted to a tag, case insensitive
+    TestCase::read("27_01_category").run_and_compare_result();
+    // answers shuffled by weight, each word once
+    TestCase::read("27_02_frequency").run_and_compare_result();
+}
+
+#[test]
+#[timeout(2000)]
+fn test_28_no_repeat() {
+    // the answer played before is skipped, then answers repeat once all are played
+    TestCase::read("28_01_no_repeat").run_and_compare_game_state();
+}